@@ -1,5 +1,5 @@
-/*use crate::builders::{Clifford, GraphBuilder};
-use crate::graph::{Graph, VertexBuilder};
+use crate::builders::{Clifford, GraphBuilder};
+use crate::graph::{EdgeType, Graph, VertexBuilder};
 use std::cmp::max;
 
 impl Clifford for GraphBuilder {
@@ -12,17 +12,17 @@ impl Clifford for GraphBuilder {
         let z = graph.add_vertex_along_wire(control, VertexBuilder::z()
             .coords(0.0, control as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
         let x = graph.add_vertex_along_wire(target, VertexBuilder::x()
             .coords(0.0, target as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
-        graph.add_wires_excluding_to(0..capacity, [target, control]);
+        graph.add_wires_excluding_to(0..capacity, [target, control]).expect("qubits are in range");
         graph.position_inputs();
         graph.position_outputs();
-        graph.add_edge(z, x);
+        graph.add_edge(z, x).expect("spiders exist");
         graph
     }
 
@@ -32,22 +32,22 @@ impl Clifford for GraphBuilder {
         let capacity = max(control, target) + 1;
         let mut graph = Graph::new(capacity);
 
-        let wire1 = graph.add_wire_to(control);
+        let wire1 = graph.add_wire_to(control).expect("qubit is in range");
         let z1 = graph.add_vertex_to_edge(wire1, VertexBuilder::z()
             .coords(0.0, control as f64)
             .build()
-        );
+        ).expect("wire exists");
 
-        let wire2 = graph.add_wire_to(target);
+        let wire2 = graph.add_wire_to(target).expect("qubit is in range");
         let z2 = graph.add_vertex_to_edge(wire2, VertexBuilder::z()
             .coords(0.0, target as f64)
             .build()
-        );
+        ).expect("wire exists");
 
         graph.position_inputs();
         graph.position_outputs();
-        graph.add_wires_excluding(0..capacity, [target, control]);
-        graph.add_edge(z1, z2);
+        graph.add_wires_excluding(0..capacity, [target, control]).expect("qubits are in range");
+        graph.add_edge_of_type(z1, z2, EdgeType::Hadamard).expect("spiders exist");
         graph
     }
 
@@ -56,11 +56,11 @@ impl Clifford for GraphBuilder {
         let capacity = qubit + 1;
         let mut graph = Graph::new(capacity);
 
-        graph.add_wires_excluding(0..capacity, [qubit]);
+        graph.add_wires_excluding(0..capacity, [qubit]).expect("qubits are in range");
         graph.add_vertex_along_wire(qubit, VertexBuilder::z_plus()
             .coords(0.0, qubit as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
         graph
     }
@@ -70,11 +70,11 @@ impl Clifford for GraphBuilder {
         let capacity = qubit + 1;
         let mut graph = Graph::new(capacity);
 
-        graph.add_wires_excluding(0..capacity, [qubit]);
+        graph.add_wires_excluding(0..capacity, [qubit]).expect("qubits are in range");
         graph.add_vertex_along_wire(qubit, VertexBuilder::z_minus()
             .coords(0.0, qubit as f64)
             .build()
-        );
+        ).expect("qubit is in range");
         graph
     }
 
@@ -83,11 +83,11 @@ impl Clifford for GraphBuilder {
         let capacity = qubit + 1;
         let mut graph = Graph::new(capacity);
 
-        graph.add_wires_excluding(0..capacity, [qubit]);
+        graph.add_wires_excluding(0..capacity, [qubit]).expect("qubits are in range");
         graph.add_vertex_along_wire(qubit, VertexBuilder::x_plus()
             .coords(0.0, qubit as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
         graph
     }
@@ -97,11 +97,11 @@ impl Clifford for GraphBuilder {
         let capacity = qubit + 1;
         let mut graph = Graph::new(capacity);
 
-        graph.add_wires_excluding(0..capacity, [qubit]);
+        graph.add_wires_excluding(0..capacity, [qubit]).expect("qubits are in range");
         graph.add_vertex_along_wire(qubit, VertexBuilder::x_minus()
             .coords(0.0, qubit as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
         graph
     }
@@ -111,11 +111,11 @@ impl Clifford for GraphBuilder {
         let capacity = qubit + 1;
         let mut graph = Graph::new(capacity);
 
-        graph.add_wires_excluding(0..capacity, [qubit]);
+        graph.add_wires_excluding(0..capacity, [qubit]).expect("qubits are in range");
         graph.add_vertex_along_wire(qubit, VertexBuilder::y_plus()
             .coords(0.0, qubit as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
         graph
     }
@@ -125,11 +125,11 @@ impl Clifford for GraphBuilder {
         let capacity = qubit + 1;
         let mut graph = Graph::new(capacity);
 
-        graph.add_wires_excluding(0..capacity, [qubit]);
+        graph.add_wires_excluding(0..capacity, [qubit]).expect("qubits are in range");
         graph.add_vertex_along_wire(qubit, VertexBuilder::y_minus()
             .coords(0.0, qubit as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
         graph
     }
@@ -139,6 +139,7 @@ impl Clifford for GraphBuilder {
 #[cfg(test)]
 mod tests {
     use crate::builders::{Clifford, GraphBuilder};
+    use crate::graph::EdgeType;
 
     #[test]
     fn cx() {
@@ -156,6 +157,7 @@ mod tests {
         assert_eq!(cz.num_outputs(), 2);
         assert_eq!(cz.num_vertices(), 6);
         assert_eq!(cz.num_edges(), 5);
+        assert_eq!(cz.edges().filter(|&&edge| edge == EdgeType::Hadamard).count(), 1);
     }
 
     #[test]
@@ -184,4 +186,4 @@ mod tests {
         assert_eq!(y_plus.num_vertices(), 3);
         assert_eq!(y_plus.num_edges(), 2);
     }
-}*/
\ No newline at end of file
+}
@@ -1,59 +1,106 @@
 use crate::builders::{Gadget, GraphBuilder};
 use crate::graph::phase::Phase;
-use crate::graph::{Graph, VertexBuilder, VertexType};
+use crate::graph::{EdgeType, Graph, GraphError, VertexBuilder, VertexType};
 
 impl Gadget for GraphBuilder {
     /// Builder: Generates a BaseGraph instance of some Pauli Gadget or Phase Gadget
-    fn gadget(pauli_string: &str, phase: Phase) -> Graph {
-        let pauli_len = pauli_string.len();
+    ///
+    /// The gadget is built in graph-like form around a Z hub, joined by a `Hadamard` edge to an
+    /// arity-1 leaf carrying `phase`. Each non-identity qubit holds a spider of the matching
+    /// Pauli type, joined to the hub by a `Hadamard` edge for Z and a `Simple` edge for X and Y.
+    /// Every Y leg is corrected by `-π/2` on the hub, so the diagram is proportional to
+    /// `exp(-i phase/2 P)` for the Pauli string `P`.
+    fn gadget(pauli_string: &str, phase: Phase) -> Result<Graph, GraphError> {
+        let paulis = pauli_string.chars()
+            .map(|pauli| match pauli {
+                'i' | 'I' => Ok(None),
+                'z' | 'Z' => Ok(Some(VertexType::Z)),
+                'x' | 'X' => Ok(Some(VertexType::X)),
+                'y' | 'Y' => Ok(Some(VertexType::Y)),
+                _ => Err(GraphError::InvalidPauli(pauli)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let pauli_len = paulis.len();
         let mut graph = Graph::new(pauli_len);
 
         let hub = graph.add_vertex(VertexBuilder::z()
             .coords(0.8, pauli_len as f64)
+            .build()
+        );
+        let leaf = graph.add_vertex(VertexBuilder::z()
+            .coords(0.8, pauli_len as f64 + 1.0)
             .phase(phase)
             .build()
         );
+        graph.add_edge_of_type(hub, leaf, EdgeType::Hadamard)?;
 
-        for (qubit, pauli) in pauli_string.chars().enumerate() {
-            let opt_vertex_type: Option<VertexType> = match pauli {
-                'i' | 'I' => None,
-                'z' | 'Z' => Some(VertexType::Z),
-                'x' | 'X' => Some(VertexType::X),
-                'y' | 'Y' => Some(VertexType::Y),
-                _ => panic!("invalid pauli character")
-            };
-
+        let mut hub_phase = Phase::zero();
+        for (qubit, opt_vertex_type) in paulis.into_iter().enumerate() {
             if let Some(vertex_type) = opt_vertex_type {
                 let vertex = graph.add_unary(qubit, VertexBuilder::new()
                     .vertex_type(vertex_type)
                     .coords(0.0, qubit as f64)
                     .build()
-                );
-                graph.add_edge(vertex, hub);
+                )?;
+                let edge_type = match vertex_type {
+                    VertexType::Z => EdgeType::Hadamard,
+                    _ => EdgeType::Simple,
+                };
+                if vertex_type == VertexType::Y {
+                    hub_phase = hub_phase + Phase::minus();
+                }
+                graph.add_edge_of_type(vertex, hub, edge_type)?;
+            } else {
+                graph.add_wire_to(qubit)?;
             }
         }
-        graph
+
+        graph.vertex_mut(hub).expect("hub exists").set_phase(hub_phase);
+        graph.position_inputs();
+        graph.position_outputs();
+        Ok(graph)
     }
 }
 
-
 #[cfg(test)]
 mod tests {
     use crate::builders::{Gadget, GraphBuilder};
     use crate::graph::phase::Phase;
+    use crate::graph::GraphError;
+    use crate::rewrite::{GadgetFusion, GraphLike};
 
     #[test]
-    #[should_panic(expected = "invalid pauli character")]
-    fn should_panic_when_invalid_pauli_character() {
-        GraphBuilder::gadget("ZXH", Phase::zero());
+    fn fails_on_invalid_pauli_character() {
+        assert_eq!(GraphBuilder::gadget("ZXH", Phase::zero()).unwrap_err(), GraphError::InvalidPauli('H'));
     }
 
     #[test]
     fn gadget() {
-        let gadget = GraphBuilder::gadget("zxy", Phase::zero());
+        let gadget = GraphBuilder::gadget("zxy", Phase::zero()).unwrap();
+        assert_eq!(gadget.num_inputs(), 3);
+        assert_eq!(gadget.num_outputs(), 3);
+        assert_eq!(gadget.num_vertices(), 11);
+        assert_eq!(gadget.num_edges(), 10);
+    }
+
+    #[test]
+    fn gadget_with_identity() {
+        let gadget = GraphBuilder::gadget("zix", Phase::zero()).unwrap();
         assert_eq!(gadget.num_inputs(), 3);
         assert_eq!(gadget.num_outputs(), 3);
-        assert_eq!(gadget.num_vertices(), 4);
-        assert_eq!(gadget.num_edges(), 3);
+        assert_eq!(gadget.num_vertices(), 10);
+        assert_eq!(gadget.num_edges(), 8);
+    }
+
+    #[test]
+    fn gadget_is_graph_like_phase_gadget() {
+        let mut gadget = GraphBuilder::gadget("zxy", Phase::plus()).unwrap();
+        gadget.to_graph_like();
+
+        let gadgets = gadget.find_gadgets();
+        assert_eq!(gadgets.len(), 1);
+        assert_eq!(gadgets[0].legs.len(), 3);
+        assert_eq!(gadget.vertex(gadgets[0].hub).unwrap().phase(), Phase::zero());
+        assert_eq!(gadget.vertex(gadgets[0].leaf).unwrap().phase(), Phase::plus());
     }
 }
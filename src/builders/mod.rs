@@ -3,14 +3,17 @@ mod clifford;
 mod gadget;
 
 use crate::graph::phase::Phase;
-use crate::graph::Graph;
+use crate::graph::{Graph, GraphError};
 
 // todo - should have build() method which perform checks
 pub struct GraphBuilder;
 
 pub trait Gadget {
     /// Creates an instance of BaseGraph representing a Pauli Gadget
-    fn gadget(pauli_string: &str, phase: Phase) -> Graph;
+    ///
+    /// Fails with [`GraphError::InvalidPauli`] if `pauli_string` has a character other than
+    /// `I`, `X`, `Y` or `Z`.
+    fn gadget(pauli_string: &str, phase: Phase) -> Result<Graph, GraphError>;
 }
 
 pub trait Pauli {
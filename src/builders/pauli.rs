@@ -1,4 +1,4 @@
-/*use crate::builders::{GraphBuilder, Pauli};
+use crate::builders::{GraphBuilder, Pauli};
 use crate::graph::{Graph, VertexBuilder};
 
 impl Pauli for GraphBuilder {
@@ -7,11 +7,11 @@ impl Pauli for GraphBuilder {
         let capacity = qubit + 1;
         let mut graph = Graph::new(capacity);
 
-        graph.add_wires_excluding(0..capacity, [qubit]);
+        graph.add_wires_excluding(0..capacity, [qubit]).expect("qubits are in range");
         graph.add_vertex_along_wire(qubit, VertexBuilder::z_pauli()
             .coords(0.0, qubit as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
         graph
     }
@@ -21,11 +21,11 @@ impl Pauli for GraphBuilder {
         let capacity = qubit + 1;
         let mut graph = Graph::new(capacity);
 
-        graph.add_wires_excluding(0..capacity, [qubit]);
+        graph.add_wires_excluding(0..capacity, [qubit]).expect("qubits are in range");
         graph.add_vertex_along_wire(qubit, VertexBuilder::x_pauli()
             .coords(0.0, qubit as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
         graph
     }
@@ -35,11 +35,11 @@ impl Pauli for GraphBuilder {
         let capacity = qubit + 1;
         let mut graph = Graph::new(capacity);
 
-        graph.add_wires_excluding(0..capacity, [qubit]);
+        graph.add_wires_excluding(0..capacity, [qubit]).expect("qubits are in range");
         graph.add_vertex_along_wire(qubit, VertexBuilder::y_pauli()
             .coords(0.0, qubit as f64)
             .build()
-        );
+        ).expect("qubit is in range");
 
         graph
     }
@@ -76,4 +76,4 @@ mod tests {
         assert_eq!(pauli_y.num_vertices(), 3);
         assert_eq!(pauli_y.num_edges(), 2);
     }
-}*/
\ No newline at end of file
+}
@@ -1,5 +1,6 @@
 use thiserror::Error;
 
+#[allow(clippy::module_inception)]
 pub mod export;
 
 #[derive(Debug, Error)]
@@ -7,6 +8,8 @@ pub enum ExportError {
     #[error("invalid phase fraction")] InvalidPhase,
     #[error("vertex {0} has no coordinates")] VertexMissingCoords(usize),
     #[error("qubit {0} has mismatching input/output")] QubitInputOutputMismatch(usize),
+    #[error("step {0} of the derivation cannot be replayed")] InvalidDerivation(usize),
+    #[error("template has no tikzpicture")] InvalidTemplate,
     #[error("formatting error: {0}")] Fmt(#[from] std::fmt::Error),
     #[error("I/O error: {0}")] Io(#[from] std::io::Error),
 }
@@ -0,0 +1,137 @@
+use crate::graph::{Coords, Graph, VertexType};
+use std::mem;
+
+impl Graph {
+    /// Returns the adjoint (dagger) of the diagram.
+    ///
+    /// Inputs and outputs are swapped, phases are negated as in [`Graph::conjugate`], and the
+    /// diagram is mirrored horizontally so that it still renders left-to-right.
+    ///
+    /// Runs in **O(n)** time, where *n* is the number of vertices.
+    pub fn adjoint(&self) -> Graph {
+        let mut graph = self.transpose();
+        graph.negate_phases();
+        graph
+    }
+
+    /// Returns the transpose of the diagram.
+    ///
+    /// Inputs and outputs are swapped and the diagram is mirrored horizontally. Phases are kept.
+    ///
+    /// Runs in **O(n)** time, where *n* is the number of vertices.
+    pub fn transpose(&self) -> Graph {
+        let mut graph = self.clone();
+        mem::swap(&mut graph.inputs, &mut graph.outputs);
+        graph.mirror();
+        graph
+    }
+
+    /// Returns the complex conjugate of the diagram.
+    ///
+    /// The phase of every Z and X spider is negated, and the scalar is conjugated. Y spiders keep
+    /// their phase, as conjugating one with phase `α` only multiplies the diagram by `e^(-iα)`,
+    /// which is folded into the scalar. Inputs, outputs and coordinates are kept.
+    ///
+    /// Runs in **O(n)** time, where *n* is the number of vertices.
+    pub fn conjugate(&self) -> Graph {
+        let mut graph = self.clone();
+        graph.negate_phases();
+        graph
+    }
+
+    /// Negates the phase of every vertex other than a Y spider, and conjugates the scalar.
+    fn negate_phases(&mut self) {
+        self.scalar = self.scalar.conjugate();
+        for vertex in self.graph.node_weights_mut() {
+            if vertex.vertex_type() == VertexType::Y {
+                self.scalar.mul_phase(-vertex.phase());
+            } else {
+                vertex.set_phase(-vertex.phase());
+            }
+        }
+    }
+
+    /// Reflects every positioned vertex about the vertical axis through the middle of the diagram.
+    fn mirror(&mut self) {
+        let (min, max) = self.vertices()
+            .filter_map(|vertex| vertex.coords())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), coords| {
+                (min.min(coords.x), max.max(coords.x))
+            });
+
+        for vertex in self.vertices_mut() {
+            if let Some(coords) = vertex.coords() {
+                vertex.set_coords(Coords { x: min + max - coords.x, y: coords.y });
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, GraphBuilder, Pauli};
+    use crate::graph::phase::Phase;
+    use crate::graph::{Coords, VertexType};
+
+    #[test]
+    fn adjoint_negates_phases() {
+        let graph = GraphBuilder::z_plus(0).adjoint();
+        let z = graph.vertices()
+            .find(|vertex| vertex.vertex_type() == VertexType::Z)
+            .unwrap();
+        assert_eq!(z.phase(), Phase::minus());
+    }
+
+    #[test]
+    fn adjoint_is_involutive() {
+        let graph = GraphBuilder::cx(0, 2);
+        let twice = graph.adjoint().adjoint();
+        assert!(graph.vertices().eq(twice.vertices()));
+        assert_eq!(graph.input_index(1), twice.input_index(1));
+    }
+
+    #[test]
+    fn transpose_swaps_boundaries() {
+        let graph = GraphBuilder::cz(0, 1);
+        let transpose = graph.transpose();
+        assert_eq!(graph.input_index(0), transpose.output_index(0));
+        assert_eq!(graph.output_index(1), transpose.input_index(1));
+
+        let input = transpose.vertex(*transpose.input_index(0).unwrap()).unwrap();
+        assert_eq!(input.coords(), Some(Coords { x: -1.0, y: 0.0 }));
+    }
+
+    #[test]
+    fn conjugate_keeps_boundaries() {
+        let graph = GraphBuilder::x_minus(0);
+        let conjugate = graph.conjugate();
+        assert_eq!(graph.input_index(0), conjugate.input_index(0));
+
+        let x = conjugate.vertices()
+            .find(|vertex| vertex.vertex_type() == VertexType::X)
+            .unwrap();
+        assert_eq!(x.phase(), Phase::plus());
+    }
+
+    #[test]
+    fn conjugate_keeps_y_phases() {
+        let y = GraphBuilder::pauli_y(0).conjugate();
+        let y = y.vertices()
+            .find(|vertex| vertex.vertex_type() == VertexType::Y)
+            .unwrap();
+        assert_eq!(y.phase(), Phase::one());
+    }
+
+    #[test]
+    fn compose_with_adjoint_cancels_phases() {
+        let graph = GraphBuilder::z_plus(0);
+        let identity = graph.compose(&graph.adjoint()).unwrap();
+        let phases: Vec<Phase> = identity.vertices()
+            .filter(|vertex| vertex.vertex_type() == VertexType::Z)
+            .map(|vertex| vertex.phase())
+            .collect();
+        assert_eq!(phases.len(), 2);
+        assert_eq!(phases[0], -phases[1].clone());
+    }
+}
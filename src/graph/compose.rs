@@ -0,0 +1,311 @@
+use crate::graph::vertex::VertexIndex;
+use crate::graph::{Coords, EdgeType, Graph, GraphError, VertexType};
+use num_complex::Complex64;
+use petgraph::prelude::EdgeRef;
+use std::collections::HashMap;
+
+impl Graph {
+    /// Composes `self` followed by `other`, plugging each output of `self` into the input of
+    /// `other` on the same qubit.
+    ///
+    /// The boundaries on either side of each plugged qubit are removed and their neighbours are
+    /// joined by a single edge. Qubits wired on only one side pass through unchanged.
+    ///
+    /// The vertices of `other` are shifted to the right of those of `self`, and the boundaries
+    /// of the result are repositioned around them. The scalars of both diagrams are multiplied,
+    /// along with the trace of any loop closed by plugging the qubits together.
+    ///
+    /// Fails with [`GraphError::BoundaryConflict`] if a qubit would end up with two inputs or two
+    /// outputs, and with [`GraphError::DisconnectedBoundary`] if a plugged boundary has no edge.
+    ///
+    /// Runs in **O(n + m)** time, where *n* and *m* are the sizes of `self` and `other`.
+    pub fn compose(&self, other: &Graph) -> Result<Graph, GraphError> {
+        let mut graph = self.clone();
+        let self_max = self.interior_x_range().map_or(0.0, |(_, max)| max);
+        let other_min = other.interior_x_range().map_or(0.0, |(min, _)| min);
+        let indices = graph.append(other, Coords { x: self_max - other_min + 1.0, y: 0.0 });
+        graph.scalar *= other.scalar.clone();
+
+        for (&qubit, &input) in other.inputs.iter() {
+            let input = indices[&input];
+            match self.outputs.get(&qubit) {
+                Some(&output) => graph.join_boundaries(qubit, output, input)?,
+                None => {
+                    if graph.inputs.insert(qubit, input).is_some() {
+                        return Err(GraphError::BoundaryConflict(qubit));
+                    }
+                }
+            }
+        }
+
+        graph.outputs.retain(|qubit, _| !other.inputs.contains_key(qubit));
+        for (&qubit, &output) in other.outputs.iter() {
+            if graph.outputs.insert(qubit, indices[&output]).is_some() {
+                return Err(GraphError::BoundaryConflict(qubit));
+            }
+        }
+
+        graph.max_qubit = self.max_qubit.max(other.max_qubit);
+        graph.position_inputs();
+        graph.position_outputs();
+        Ok(graph)
+    }
+
+    /// Places `other` in parallel below `self`, forming their tensor product.
+    ///
+    /// The qubits of `other` are shifted by `self.max_qubit()`, and its vertices are moved down
+    /// by the same number of rows. The boundaries of the result are repositioned so that every
+    /// qubit starts and ends in the same column.
+    ///
+    /// Fails with [`GraphError::BoundaryConflict`] if a shifted qubit of `other` already has an
+    /// input or output in `self`.
+    ///
+    /// Runs in **O(n + m)** time, where *n* and *m* are the sizes of `self` and `other`.
+    pub fn tensor(&self, other: &Graph) -> Result<Graph, GraphError> {
+        let mut graph = self.clone();
+        let shift = self.max_qubit;
+        let indices = graph.append(other, Coords { x: 0.0, y: shift as f64 });
+        graph.scalar *= other.scalar.clone();
+
+        for (&qubit, &input) in other.inputs.iter() {
+            if graph.inputs.insert(qubit + shift, indices[&input]).is_some() {
+                return Err(GraphError::BoundaryConflict(qubit + shift));
+            }
+        }
+        for (&qubit, &output) in other.outputs.iter() {
+            if graph.outputs.insert(qubit + shift, indices[&output]).is_some() {
+                return Err(GraphError::BoundaryConflict(qubit + shift));
+            }
+        }
+
+        graph.max_qubit = self.max_qubit + other.max_qubit;
+        graph.position_inputs();
+        graph.position_outputs();
+        Ok(graph)
+    }
+
+    /// Copies every vertex and edge of `other` into `self`, shifting coordinates by `offset`.
+    ///
+    /// Inputs and outputs of `other` are not registered on `self`.
+    ///
+    /// Returns a map from the indices of `other` to the new indices in `self`.
+    fn append(&mut self, other: &Graph, offset: Coords) -> HashMap<VertexIndex, VertexIndex> {
+        let mut indices = HashMap::with_capacity(other.num_vertices());
+        for (index, vertex) in other.enumerate_vertices() {
+            let mut vertex = vertex.clone();
+            if let Some(coords) = vertex.coords() {
+                vertex.set_coords(Coords { x: coords.x + offset.x, y: coords.y + offset.y });
+            }
+            indices.insert(index, self.add_vertex(vertex));
+        }
+
+        for edge in other.enumerate_edges() {
+            self.add_edge_of_type(indices[&edge.source()], indices[&edge.target()], *edge.weight())
+                .expect("endpoints were copied");
+        }
+        indices
+    }
+
+    /// Removes the boundaries `output` and `input` of `qubit`, joining their neighbours by a
+    /// single edge.
+    ///
+    /// The new edge keeps the stored direction of the edge at `left` if it is a Y spider, see
+    /// [`VertexType::Y`], and of the edge at `right` otherwise.
+    ///
+    /// Fails with [`GraphError::DisconnectedBoundary`] if either boundary has no edge.
+    fn join_boundaries(&mut self, qubit: usize, output: VertexIndex, input: VertexIndex) -> Result<(), GraphError> {
+        let (left, left_type, left_is_source) = self.boundary_neighbour(qubit, output)?;
+        let (right, right_type, right_is_source) = self.boundary_neighbour(qubit, input)?;
+        self.remove_vertex(output);
+        self.remove_vertex(input);
+
+        // Boundaries wired straight into each other close a loop, leaving only its trace
+        if left == input {
+            match left_type {
+                EdgeType::Simple => self.scalar.mul_sqrt2_power(2),
+                EdgeType::Hadamard => self.scalar.mul_factor(Complex64::ZERO),
+            }
+            return Ok(());
+        }
+        let left_is_y = self.vertex(left).is_some_and(|vertex| vertex.vertex_type() == VertexType::Y);
+        let forward = if left_is_y { left_is_source } else { !right_is_source };
+        let (source, target) = if forward { (left, right) } else { (right, left) };
+        self.add_edge_of_type(source, target, left_type.compose(right_type)).expect("boundary neighbours exist");
+        Ok(())
+    }
+
+    /// Returns the vertex attached to the boundary `boundary` of `qubit`, the type of the
+    /// connecting edge, and whether the vertex is the stored source of that edge.
+    ///
+    /// Fails with [`GraphError::DisconnectedBoundary`] if `boundary` has no edge.
+    fn boundary_neighbour(&self, qubit: usize, boundary: VertexIndex) -> Result<(VertexIndex, EdgeType, bool), GraphError> {
+        self.graph
+            .edges(boundary)
+            .next()
+            .map(|edge| {
+                let (source, _) = self.graph.edge_endpoints(edge.id()).expect("edge exists");
+                (edge.target(), *edge.weight(), source == edge.target())
+            })
+            .ok_or(GraphError::DisconnectedBoundary(qubit))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, Gadget, GraphBuilder};
+    use crate::graph::phase::Phase;
+    use crate::graph::{Coords, EdgeType, Graph, GraphError, VertexBuilder, VertexType};
+    use crate::tensor::{Evaluate, Matrix};
+    use num_complex::Complex64;
+    use petgraph::prelude::EdgeRef;
+
+    #[test]
+    fn compose_single_qubit_gates() {
+        let graph = GraphBuilder::z_plus(0).compose(&GraphBuilder::x_plus(0)).unwrap();
+        assert_eq!(graph.num_inputs(), 1);
+        assert_eq!(graph.num_outputs(), 1);
+        assert_eq!(graph.num_vertices(), 4);
+        assert_eq!(graph.num_edges(), 3);
+    }
+
+    #[test]
+    fn compose_two_qubit_gates() {
+        let graph = GraphBuilder::cx(0, 1).compose(&GraphBuilder::cz(0, 1)).unwrap();
+        assert_eq!(graph.num_inputs(), 2);
+        assert_eq!(graph.num_outputs(), 2);
+        assert_eq!(graph.num_vertices(), 8);
+        assert_eq!(graph.num_edges(), 8);
+    }
+
+    #[test]
+    fn compose_passes_through_unmatched_qubits() {
+        let graph = GraphBuilder::cx(0, 1).compose(&GraphBuilder::z_plus(2)).unwrap();
+        assert_eq!(graph.max_qubit(), 3);
+        assert_eq!(graph.num_inputs(), 3);
+        assert_eq!(graph.num_outputs(), 3);
+        assert_eq!(graph.num_vertices(), 9);
+        assert_eq!(graph.num_edges(), 7);
+    }
+
+    #[test]
+    fn compose_joins_edge_types() {
+        let mut hadamard = Graph::new(1);
+        hadamard.add_wire_to(0).unwrap();
+        *hadamard.edges_mut().next().unwrap() = EdgeType::Hadamard;
+
+        let graph = GraphBuilder::z_plus(0).compose(&hadamard).unwrap().compose(&hadamard).unwrap();
+        let types: Vec<EdgeType> = graph.edges().copied().collect();
+        assert_eq!(types, [EdgeType::Simple, EdgeType::Simple]);
+
+        let graph = GraphBuilder::z_plus(0).compose(&hadamard).unwrap();
+        assert_eq!(graph.edges().filter(|&&edge| edge == EdgeType::Hadamard).count(), 1);
+    }
+
+    #[test]
+    fn compose_places_other_to_the_right() {
+        let graph = GraphBuilder::z_plus(0).compose(&GraphBuilder::z_minus(0)).unwrap();
+        let mut xs: Vec<f64> = graph.vertices()
+            .filter(|vertex| vertex.vertex_type() == VertexType::Z)
+            .map(|vertex| vertex.coords().unwrap().x)
+            .collect();
+        xs.sort_by(f64::total_cmp);
+        assert_eq!(xs, [0.0, 1.0]);
+
+        let output = graph.vertex(*graph.output_index(0).unwrap()).unwrap();
+        assert_eq!(output.coords(), Some(Coords { x: 2.0, y: 0.0 }));
+    }
+
+    #[test]
+    fn compose_keeps_direction_of_y_spider_edges() {
+        // The Y spider is the stored target of the edge to its output
+        let mut graph = Graph::new(1);
+        let input = graph.add_vertex(VertexBuilder::b().build());
+        let y = graph.add_vertex(VertexBuilder::y().build());
+        let output = graph.add_vertex(VertexBuilder::b().build());
+        graph.add_edge(input, y).unwrap();
+        graph.add_edge(output, y).unwrap();
+        graph.set_input(0, input).unwrap();
+        graph.set_output(0, output).unwrap();
+        let mut wire = Graph::new(1);
+        wire.add_wire_to(0).unwrap();
+
+        let graph = graph.compose(&wire).unwrap();
+        let output = *graph.output_index(0).unwrap();
+        assert!(graph.enumerate_edges().any(|edge| edge.source() == output && edge.target() == y));
+    }
+
+    #[test]
+    fn compose_cup_with_cap_leaves_trace_of_loop() {
+        for (edge_type, trace) in [(EdgeType::Simple, 2.0), (EdgeType::Hadamard, 0.0)] {
+            let mut cup = Graph::new(2);
+            let outputs = [cup.add_vertex(VertexBuilder::b().build()), cup.add_vertex(VertexBuilder::b().build())];
+            cup.add_edge_of_type(outputs[0], outputs[1], edge_type).unwrap();
+            cup.set_output(0, outputs[0]).unwrap();
+            cup.set_output(1, outputs[1]).unwrap();
+            let mut cap = Graph::new(2);
+            let inputs = [cap.add_vertex(VertexBuilder::b().build()), cap.add_vertex(VertexBuilder::b().build())];
+            cap.add_edge(inputs[0], inputs[1]).unwrap();
+            cap.set_input(0, inputs[0]).unwrap();
+            cap.set_input(1, inputs[1]).unwrap();
+
+            let graph = cup.compose(&cap).unwrap();
+            assert_eq!(graph.num_vertices(), 0);
+            let expected = &cap.to_matrix() * &cup.to_matrix();
+            assert!(graph.to_matrix().approx_eq(&expected, 1e-9), "{edge_type:?}");
+            assert!(expected.approx_eq(&Matrix::new(1, 1, vec![Complex64::new(trace, 0.0)]), 1e-9), "{edge_type:?}");
+        }
+    }
+
+    #[test]
+    fn tensor_gadgets() {
+        let graph = GraphBuilder::gadget("zx", Phase::plus()).unwrap()
+            .tensor(&GraphBuilder::gadget("y", Phase::minus()).unwrap()).unwrap();
+        assert_eq!(graph.max_qubit(), 3);
+        assert_eq!(graph.num_inputs(), 3);
+        assert_eq!(graph.num_outputs(), 3);
+        assert_eq!(graph.num_vertices(), 13);
+        assert_eq!(graph.num_edges(), 11);
+
+        let y = graph.vertices()
+            .find(|vertex| vertex.vertex_type() == VertexType::Y)
+            .unwrap();
+        assert_eq!(y.coords(), Some(Coords { x: 0.0, y: 2.0 }));
+    }
+
+    #[test]
+    fn tensor_shifts_boundaries() {
+        let graph = GraphBuilder::z_plus(0).tensor(&GraphBuilder::cx(0, 1)).unwrap();
+        for qubit in 0..3 {
+            let input = graph.vertex(*graph.input_index(qubit).unwrap()).unwrap();
+            assert!(input.is_boundary());
+            assert_eq!(input.coords().unwrap().y, qubit as f64);
+        }
+        assert!(graph.edge_index(
+            *graph.input_index(1).unwrap(),
+            *graph.output_index(1).unwrap(),
+        ).is_none());
+    }
+
+    #[test]
+    fn fails_when_outputs_conflict() {
+        let mut effect = Graph::new(1);
+        let output = effect.add_vertex(VertexBuilder::b().build());
+        let z = effect.add_vertex(VertexBuilder::z().build());
+        effect.add_edge(z, output).unwrap();
+        effect.set_output(0, output).unwrap();
+
+        assert!(matches!(effect.compose(&effect), Err(GraphError::BoundaryConflict(0))));
+    }
+
+    #[test]
+    fn fails_when_plugged_boundary_is_disconnected() {
+        let mut graph = Graph::new(1);
+        let output = graph.add_vertex(VertexBuilder::b().build());
+        graph.set_output(0, output).unwrap();
+        let mut wire = Graph::new(1);
+        wire.add_wire_to(0).unwrap();
+
+        assert!(matches!(graph.compose(&wire), Err(GraphError::DisconnectedBoundary(0))));
+    }
+}
@@ -1,16 +1,19 @@
+use crate::graph::phase::Phase;
+use crate::graph::scalar::Scalar;
 use crate::graph::vertex::VertexIndex;
-use crate::graph::{EdgeType, Vertex};
-use petgraph::prelude::{EdgeIndex, StableUnGraph};
+use crate::graph::{Coords, EdgeType, GraphError, Vertex, VertexBuilder, VertexType};
+use petgraph::prelude::{EdgeIndex, EdgeRef, StableUnGraph};
 use petgraph::stable_graph::EdgeReference;
 use petgraph::visit::{IntoEdgeReferences, IntoNodeReferences};
 use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct Graph {
-    max_qubit: usize,
-    inputs: HashMap<usize, VertexIndex>,
-    outputs: HashMap<usize, VertexIndex>,
-    graph: StableUnGraph<Vertex, EdgeType>,
+    pub(super) max_qubit: usize,
+    pub(super) inputs: HashMap<usize, VertexIndex>,
+    pub(super) outputs: HashMap<usize, VertexIndex>,
+    pub(super) graph: StableUnGraph<Vertex, EdgeType>,
+    pub(super) scalar: Scalar,
 }
 
 impl Graph {
@@ -21,25 +24,36 @@ impl Graph {
             inputs: HashMap::new(),
             outputs: HashMap::new(),
             graph: StableUnGraph::with_capacity(capacity, capacity),
+            scalar: Scalar::one(),
         }
     }
 
     /// Sets the input vertex for a given qubit, overwriting any existing entry.
     ///
-    /// Inserts or updates the mapping in `inputs` between the qubit index and its vertex.
+    /// Inserts or updates the mapping in `inputs` between the qubit index and its vertex,
+    /// which is expected to be a `VertexType::B` boundary.
+    ///
+    /// Fails with [`GraphError::MissingVertex`] if the vertex does not exist.
     ///
     /// Runs in **O(1)** time.
-    pub fn set_input(&mut self, qubit: usize, vertex_index: VertexIndex) {
+    pub fn set_input(&mut self, qubit: usize, vertex_index: VertexIndex) -> Result<(), GraphError> {
+        self.check_vertex(vertex_index)?;
         self.inputs.insert(qubit, vertex_index);
+        Ok(())
     }
 
     /// Sets the output vertex for a given qubit, overwriting any existing entry.
     ///
-    /// Inserts or updates the mapping in `outputs` between the qubit index and its vertex.
+    /// Inserts or updates the mapping in `outputs` between the qubit index and its vertex,
+    /// which is expected to be a `VertexType::B` boundary.
+    ///
+    /// Fails with [`GraphError::MissingVertex`] if the vertex does not exist.
     ///
     /// Runs in **O(1)** time.
-    pub fn set_output(&mut self, qubit: usize, vertex_index: VertexIndex) {
+    pub fn set_output(&mut self, qubit: usize, vertex_index: VertexIndex) -> Result<(), GraphError> {
+        self.check_vertex(vertex_index)?;
         self.outputs.insert(qubit, vertex_index);
+        Ok(())
     }
 
     /// Adds a vertex to the graph.
@@ -51,16 +65,16 @@ impl Graph {
         self.graph.add_node(vertex)
     }
 
-    /// Adds a vertex with one input and one output to the graph.
+    /// Adds a vertex on a new wire of `qubit`, between a fresh input and output boundary.
     ///
     /// Returns the index of the new vertex.
     ///
+    /// Fails as [`Graph::add_wire_to`] does.
+    ///
     /// Runs in **O(1)** time.
-    pub fn add_unary(&mut self, qubit: usize, vertex: Vertex) -> VertexIndex {
-        let vertex_index = self.add_vertex(vertex);
-        self.set_input(qubit, vertex_index);
-        self.set_output(qubit, vertex_index);
-        vertex_index
+    pub fn add_unary(&mut self, qubit: usize, vertex: Vertex) -> Result<VertexIndex, GraphError> {
+        let wire = self.add_wire_to(qubit)?;
+        self.add_vertex_to_edge(wire, vertex)
     }
 
     /// Adds a `Simple` edge between `source` and `target` to the graph.
@@ -69,8 +83,10 @@ impl Graph {
     ///
     /// `Graph` allows adding parallel ("duplicate") edges.
     ///
+    /// Fails with [`GraphError::MissingVertex`] if either endpoint does not exist.
+    ///
     /// Runs in **O(1)** time.
-    pub fn add_edge(&mut self, source: VertexIndex, target: VertexIndex) -> EdgeIndex {
+    pub fn add_edge(&mut self, source: VertexIndex, target: VertexIndex) -> Result<EdgeIndex, GraphError> {
         self.add_edge_of_type(source, target, EdgeType::Simple)
     }
 
@@ -80,9 +96,168 @@ impl Graph {
     ///
     /// `Graph` allows adding parallel ("duplicate") edges.
     ///
+    /// Fails with [`GraphError::MissingVertex`] if either endpoint does not exist.
+    ///
     /// Runs in **O(1)** time.
-    pub fn add_edge_of_type(&mut self, source: VertexIndex, target: VertexIndex, edge_type: EdgeType) -> EdgeIndex {
-        self.graph.add_edge(source, target, edge_type)
+    pub fn add_edge_of_type(
+        &mut self,
+        source: VertexIndex,
+        target: VertexIndex,
+        edge_type: EdgeType,
+    ) -> Result<EdgeIndex, GraphError> {
+        self.check_vertex(source)?;
+        self.check_vertex(target)?;
+        Ok(self.graph.add_edge(source, target, edge_type))
+    }
+
+    /// Adds a bare wire on `qubit`, consisting of an input boundary connected to an output
+    /// boundary by a `Simple` edge.
+    ///
+    /// Returns the index of the new edge.
+    ///
+    /// Fails with [`GraphError::QubitOutOfRange`] if `qubit` is out of range, and with
+    /// [`GraphError::BoundaryConflict`] if it already has an input or output.
+    ///
+    /// Runs in **O(1)** time.
+    pub fn add_wire_to(&mut self, qubit: usize) -> Result<EdgeIndex, GraphError> {
+        if qubit >= self.max_qubit {
+            return Err(GraphError::QubitOutOfRange(qubit));
+        }
+        if self.inputs.contains_key(&qubit) || self.outputs.contains_key(&qubit) {
+            return Err(GraphError::BoundaryConflict(qubit));
+        }
+
+        let input = self.add_vertex(VertexBuilder::b()
+            .coords(-1.0, qubit as f64)
+            .build()
+        );
+        let output = self.add_vertex(VertexBuilder::b()
+            .coords(1.0, qubit as f64)
+            .build()
+        );
+
+        self.inputs.insert(qubit, input);
+        self.outputs.insert(qubit, output);
+        Ok(self.graph.add_edge(input, output, EdgeType::Simple))
+    }
+
+    /// Adds a bare wire on every qubit in `qubits` that does not appear in `excluded`.
+    ///
+    /// Returns the indices of the new edges, in the order the qubits were visited.
+    ///
+    /// Fails as [`Graph::add_wire_to`] does, keeping the wires added before the failure.
+    ///
+    /// Runs in **O(`qubits` × `excluded`)** time.
+    pub fn add_wires_excluding_to(
+        &mut self,
+        qubits: impl IntoIterator<Item = usize>,
+        excluded: impl IntoIterator<Item = usize>,
+    ) -> Result<Vec<EdgeIndex>, GraphError> {
+        let excluded: Vec<usize> = excluded.into_iter().collect();
+        qubits.into_iter()
+            .filter(|qubit| !excluded.contains(qubit))
+            .map(|qubit| self.add_wire_to(qubit))
+            .collect()
+    }
+
+    /// Adds a bare wire on every qubit in `qubits` that does not appear in `excluded`.
+    ///
+    /// Fails as [`Graph::add_wire_to`] does, keeping the wires added before the failure.
+    ///
+    /// Runs in **O(`qubits` × `excluded`)** time.
+    pub fn add_wires_excluding(
+        &mut self,
+        qubits: impl IntoIterator<Item = usize>,
+        excluded: impl IntoIterator<Item = usize>,
+    ) -> Result<(), GraphError> {
+        self.add_wires_excluding_to(qubits, excluded).map(|_| ())
+    }
+
+    /// Splits `edge` in two by inserting `vertex` between its endpoints.
+    ///
+    /// The half attached to the original source keeps the edge type, the other half is `Simple`.
+    ///
+    /// Returns the index of the new vertex.
+    ///
+    /// Fails with [`GraphError::MissingEdge`] if `edge` does not exist.
+    ///
+    /// Runs in **O(`degree`)** time, where *`degree`* is the degree of the edge endpoints.
+    pub fn add_vertex_to_edge(&mut self, edge: EdgeIndex, vertex: Vertex) -> Result<VertexIndex, GraphError> {
+        let (source, target) = self.graph.edge_endpoints(edge).ok_or(GraphError::MissingEdge(edge.index()))?;
+        let edge_type = self.graph.remove_edge(edge).expect("edge exists");
+
+        let vertex_index = self.add_vertex(vertex);
+        self.graph.add_edge(source, vertex_index, edge_type);
+        self.graph.add_edge(vertex_index, target, EdgeType::Simple);
+        Ok(vertex_index)
+    }
+
+    /// Adds `vertex` on the wire of `qubit`, just before its output boundary.
+    ///
+    /// A bare wire is created first if `qubit` has no boundaries yet.
+    ///
+    /// Returns the index of the new vertex.
+    ///
+    /// Fails as [`Graph::add_wire_to`] does if a wire has to be created, and with
+    /// [`GraphError::DisconnectedBoundary`] if the output boundary of `qubit` is not connected to
+    /// the rest of the wire.
+    ///
+    /// Runs in **O(`degree`)** time, where *`degree`* is the degree of the output's neighbour.
+    pub fn add_vertex_along_wire(&mut self, qubit: usize, vertex: Vertex) -> Result<VertexIndex, GraphError> {
+        if !self.outputs.contains_key(&qubit) {
+            self.add_wire_to(qubit)?;
+        }
+
+        let output = self.outputs[&qubit];
+        let (edge, previous, edge_type) = self.graph
+            .edges(output)
+            .next()
+            .map(|edge| (edge.id(), edge.target(), *edge.weight()))
+            .ok_or(GraphError::DisconnectedBoundary(qubit))?;
+
+        // Rebuild both halves so the wire stays oriented from input to output
+        self.graph.remove_edge(edge);
+        let vertex_index = self.add_vertex(vertex);
+        self.graph.add_edge(previous, vertex_index, edge_type);
+        self.graph.add_edge(vertex_index, output, EdgeType::Simple);
+        Ok(vertex_index)
+    }
+
+    /// Places every input boundary one unit to the left of the leftmost positioned
+    /// non-boundary vertex, on the row of its qubit.
+    ///
+    /// Runs in **O(n)** time, where *n* is the number of vertices.
+    pub fn position_inputs(&mut self) {
+        let x = self.interior_x_range().map_or(0.0, |(min, _)| min) - 1.0;
+        for (&qubit, &index) in self.inputs.iter() {
+            if let Some(vertex) = self.graph.node_weight_mut(index) {
+                vertex.set_coords(Coords { x, y: qubit as f64 });
+            }
+        }
+    }
+
+    /// Places every output boundary one unit to the right of the rightmost positioned
+    /// non-boundary vertex, on the row of its qubit.
+    ///
+    /// Runs in **O(n)** time, where *n* is the number of vertices.
+    pub fn position_outputs(&mut self) {
+        let x = self.interior_x_range().map_or(0.0, |(_, max)| max) + 1.0;
+        for (&qubit, &index) in self.outputs.iter() {
+            if let Some(vertex) = self.graph.node_weight_mut(index) {
+                vertex.set_coords(Coords { x, y: qubit as f64 });
+            }
+        }
+    }
+
+    /// Returns the smallest and largest x coordinates of positioned non-boundary vertices.
+    pub(super) fn interior_x_range(&self) -> Option<(f64, f64)> {
+        self.vertices()
+            .filter(|vertex| !vertex.is_boundary())
+            .filter_map(|vertex| vertex.coords())
+            .fold(None, |range, coords| match range {
+                None => Some((coords.x, coords.x)),
+                Some((min, max)) => Some((f64::min(min, coords.x), f64::max(max, coords.x))),
+            })
     }
 
     /// Removes vertex by index from the graph if it exists.
@@ -100,10 +275,17 @@ impl Graph {
     ///
     /// Returns the edge type if it exists and `None` if it does not.
     pub fn remove_edge(&mut self, source: VertexIndex, target: VertexIndex) -> Option<EdgeType> {
-        self.graph.remove_edge(self.graph
-            .find_edge(source, target)
-            .expect("No edge found.")
-        )
+        let edge = self.graph.find_edge(source, target)?;
+        self.graph.remove_edge(edge)
+    }
+
+    /// Removes the edge at `index`.
+    ///
+    /// Returns the edge type if it exists and `None` if it does not.
+    ///
+    /// Runs in **O(`degree`)** time, where *`degree`* is the degree of the edge endpoints.
+    pub fn remove_edge_at(&mut self, index: EdgeIndex) -> Option<EdgeType> {
+        self.graph.remove_edge(index)
     }
 
     /// Removes the input for the given qubit.
@@ -120,6 +302,53 @@ impl Graph {
         self.outputs.remove(&qubit);
     }
 
+    /// Returns true if the vertex at `index` exists and is a boundary.
+    ///
+    /// Runs in **O(1)** time.
+    pub fn is_boundary(&self, index: VertexIndex) -> bool {
+        self.vertex(index).is_some_and(Vertex::is_boundary)
+    }
+
+    /// Returns the qubit whose input or output is the boundary at `index`, if any.
+    ///
+    /// Runs in **O(n)** time, where *n* is the number of boundaries.
+    pub fn boundary_qubit(&self, index: VertexIndex) -> Option<usize> {
+        self.inputs.iter()
+            .chain(self.outputs.iter())
+            .find(|(_, &boundary)| boundary == index)
+            .map(|(&qubit, _)| qubit)
+    }
+
+    /// Returns the global scalar of the diagram.
+    pub fn scalar(&self) -> &Scalar {
+        &self.scalar
+    }
+
+    /// Returns a mutable reference to the global scalar of the diagram.
+    pub fn scalar_mut(&mut self) -> &mut Scalar {
+        &mut self.scalar
+    }
+
+    /// Replaces the phase of every vertex and of the scalar with `map` of it, for example to
+    /// substitute a value for a symbolic variable.
+    ///
+    /// Runs in **O(n)** time, where *n* is the number of vertices.
+    pub fn map_phases(&mut self, map: impl Fn(&Phase) -> Phase) {
+        for vertex in self.graph.node_weights_mut() {
+            vertex.set_phase(map(&vertex.phase()));
+        }
+        let phase = self.scalar.phase().clone();
+        self.scalar.mul_phase(map(&phase) + -phase);
+    }
+
+    /// Fails with [`GraphError::MissingVertex`] if the vertex at `index` does not exist.
+    fn check_vertex(&self, index: VertexIndex) -> Result<(), GraphError> {
+        match self.graph.contains_node(index) {
+            true => Ok(()),
+            false => Err(GraphError::MissingVertex(index.index())),
+        }
+    }
+
     /// Returns an immutable reference to a vertex by index
     pub fn vertex(&self, index: VertexIndex) -> Option<&Vertex> {
         self.graph.node_weight(index)
@@ -130,6 +359,11 @@ impl Graph {
         self.graph.node_weight_mut(index)
     }
 
+    /// Returns a mutable reference to an edge type by its index.
+    pub fn edge_mut(&mut self, index: EdgeIndex) -> Option<&mut EdgeType> {
+        self.graph.edge_weight_mut(index)
+    }
+
     /// Returns the index of the edge connecting `source` and `target`, if it exists.
     ///
     /// Runs in **O(`degree`)** time, where *`degree`* is the degree of the searched vertex.
@@ -137,6 +371,46 @@ impl Graph {
         self.graph.find_edge(source, target)
     }
 
+    /// Returns the `source` and `target` of the edge at `index`, in the order it was added.
+    ///
+    /// Runs in **O(1)** time.
+    pub fn edge_endpoints(&self, index: EdgeIndex) -> Option<(VertexIndex, VertexIndex)> {
+        self.graph.edge_endpoints(index)
+    }
+
+    /// Returns the type of the edge connecting `source` and `target`, if it exists.
+    ///
+    /// Runs in **O(`degree`)** time, where *`degree`* is the degree of the searched vertex.
+    pub fn edge_type(&self, source: VertexIndex, target: VertexIndex) -> Option<EdgeType> {
+        self.edge_index(source, target).and_then(|edge| self.graph.edge_weight(edge).copied())
+    }
+
+    /// Returns an iterator over every edge incident to the vertex at `index`.
+    ///
+    /// Each item yielded is an EdgeReference whose `source` is `index` and whose `target` is
+    /// the neighbour. Self-loops are yielded once.
+    ///
+    /// Runs in **O(`degree`)** time, where *`degree`* is the degree of the vertex.
+    pub fn incident_edges(&self, index: VertexIndex) -> impl Iterator<Item=EdgeReference<'_, EdgeType>> {
+        self.graph.edges(index)
+    }
+
+    /// Returns an iterator over the neighbours of the vertex at `index`.
+    ///
+    /// A neighbour is yielded once for every edge connecting it to `index`.
+    ///
+    /// Runs in **O(`degree`)** time, where *`degree`* is the degree of the vertex.
+    pub fn neighbours(&self, index: VertexIndex) -> impl Iterator<Item=VertexIndex> + '_ {
+        self.incident_edges(index).map(|edge| edge.target())
+    }
+
+    /// Returns the number of edges incident to the vertex at `index`.
+    ///
+    /// Runs in **O(`degree`)** time, where *`degree`* is the degree of the vertex.
+    pub fn degree(&self, index: VertexIndex) -> usize {
+        self.incident_edges(index).count()
+    }
+
     /// Returns an iterator over all immutable vertex references in the graph.
     ///
     /// Runs in **O(n)** time, where *n* is the number of vertices.
@@ -233,6 +507,27 @@ impl Graph {
         self.graph.node_count()
     }
 
+    /// Returns the number of spiders whose phase is an odd multiple of `π/4`.
+    ///
+    /// Runs in **O(n)** time, where *n* is the number of vertices.
+    pub fn t_count(&self) -> usize {
+        self.spiders().filter(|(_, vertex)| vertex.phase().is_t_like()).count()
+    }
+
+    /// Returns the spiders whose phase is not a multiple of `π/2`, including those with symbolic
+    /// or floating-point phases.
+    ///
+    /// Runs in **O(n)** time, where *n* is the number of vertices.
+    pub fn non_clifford_vertices(&self) -> impl Iterator<Item = VertexIndex> + '_ {
+        self.spiders().filter(|(_, vertex)| vertex.phase().is_non_clifford()).map(|(index, _)| index)
+    }
+
+    /// Returns the Z, X and Y spiders of the graph.
+    fn spiders(&self) -> impl Iterator<Item = (VertexIndex, &Vertex)> {
+        self.enumerate_vertices()
+            .filter(|(_, vertex)| matches!(vertex.vertex_type(), VertexType::Z | VertexType::X | VertexType::Y))
+    }
+
     /// Returns total number of edges in the graph.
     ///
     /// Runs in **O(1)** time.
@@ -276,6 +571,21 @@ mod tests {
         assert_eq!(graph.num_outputs(), 0);
     }
 
+    #[test]
+    fn counts_t_and_non_clifford_vertices() {
+        let mut graph = Graph::new(1);
+        graph.add_vertex_along_wire(0, VertexBuilder::z_plus().build()).unwrap();
+        let t = graph.add_vertex_along_wire(0, VertexBuilder::z().phase(Phase::from_ratio(1, 4)).build()).unwrap();
+        graph.add_vertex_along_wire(0, VertexBuilder::x_pauli().build()).unwrap();
+        let t_dagger = graph.add_vertex_along_wire(0, VertexBuilder::y().phase(Phase::from_ratio(-1, 4)).build()).unwrap();
+        let theta = graph.add_vertex_along_wire(0, VertexBuilder::x().phase(Phase::parameter("\\theta")).build()).unwrap();
+
+        assert_eq!(graph.t_count(), 2);
+        let mut vertices: Vec<VertexIndex> = graph.non_clifford_vertices().collect();
+        vertices.sort();
+        assert_eq!(vertices, [t, t_dagger, theta]);
+    }
+
     /// Test Graph::add_vertex(...)
     #[test]
     fn can_add_vertex() {
@@ -290,9 +600,84 @@ mod tests {
         let mut graph = Graph::new(1);
         let z = graph.add_vertex(VertexBuilder::z().build());
         let x = graph.add_vertex(VertexBuilder::x().build());
-        graph.add_edge(z, x);
+        graph.add_edge(z, x).unwrap();
 
         assert_eq!(graph.num_vertices(), 2);
         assert_eq!(graph.num_edges(), 1);
     }
-}
\ No newline at end of file
+
+    /// Test Graph::add_vertex_along_wire(...)
+    #[test]
+    fn can_add_vertex_along_wire() {
+        let mut graph = Graph::new(1);
+        let z = graph.add_vertex_along_wire(0, VertexBuilder::z().build()).unwrap();
+        let x = graph.add_vertex_along_wire(0, VertexBuilder::x().build()).unwrap();
+
+        assert_eq!(graph.num_vertices(), 4);
+        assert_eq!(graph.num_edges(), 3);
+        assert!(graph.edge_index(*graph.input_index(0).unwrap(), z).is_some());
+        assert!(graph.edge_index(z, x).is_some());
+        assert!(graph.edge_index(x, *graph.output_index(0).unwrap()).is_some());
+    }
+
+    /// Test Graph::position_inputs(...) and Graph::position_outputs(...)
+    #[test]
+    fn can_position_boundaries() {
+        let mut graph = Graph::new(2);
+        graph.add_vertex_along_wire(0, VertexBuilder::z().coords(2.0, 0.0).build()).unwrap();
+        graph.add_wire_to(1).unwrap();
+        graph.position_inputs();
+        graph.position_outputs();
+
+        let input = graph.vertex(*graph.input_index(1).unwrap()).unwrap();
+        let output = graph.vertex(*graph.output_index(1).unwrap()).unwrap();
+        assert_eq!(input.coords(), Some(Coords { x: 1.0, y: 1.0 }));
+        assert_eq!(output.coords(), Some(Coords { x: 3.0, y: 1.0 }));
+    }
+
+    #[test]
+    fn fails_when_wire_already_exists() {
+        let mut graph = Graph::new(1);
+        graph.add_wire_to(0).unwrap();
+        assert_eq!(graph.add_wire_to(0), Err(GraphError::BoundaryConflict(0)));
+        assert_eq!(graph.add_unary(1, VertexBuilder::z().build()), Err(GraphError::QubitOutOfRange(1)));
+    }
+
+    #[test]
+    fn fails_on_missing_vertices_and_edges() {
+        let mut graph = Graph::new(1);
+        let wire = graph.add_wire_to(0).unwrap();
+        graph.remove_edge_at(wire);
+        assert_eq!(graph.add_vertex_to_edge(wire, VertexBuilder::x().build()), Err(GraphError::MissingEdge(wire.index())));
+
+        let z = graph.add_vertex(VertexBuilder::z().build());
+
+        let removed = graph.add_vertex(VertexBuilder::x().build());
+        graph.remove_vertex(removed);
+        assert_eq!(graph.add_edge(z, removed), Err(GraphError::MissingVertex(removed.index())));
+        assert_eq!(graph.set_output(0, removed), Err(GraphError::MissingVertex(removed.index())));
+        assert_eq!(graph.remove_edge(z, z), None);
+    }
+
+    #[test]
+    fn fails_when_output_is_disconnected() {
+        let mut graph = Graph::new(1);
+        let output = graph.add_vertex(VertexBuilder::b().build());
+        graph.set_output(0, output).unwrap();
+        let result = graph.add_vertex_along_wire(0, VertexBuilder::z().build());
+        assert_eq!(result, Err(GraphError::DisconnectedBoundary(0)));
+    }
+
+    #[test]
+    fn self_loops_are_incident_once() {
+        let mut graph = Graph::new(1);
+        let z = graph.add_vertex(VertexBuilder::z().build());
+        let x = graph.add_vertex(VertexBuilder::x().build());
+        graph.add_edge(z, z).unwrap();
+        graph.add_edge_of_type(z, x, EdgeType::Hadamard).unwrap();
+
+        assert_eq!(graph.degree(z), 2);
+        assert_eq!(graph.neighbours(z).filter(|&neighbour| neighbour == z).count(), 1);
+        assert_eq!(graph.edge_type(x, z), Some(EdgeType::Hadamard));
+    }
+}
@@ -1,10 +1,40 @@
+#[allow(clippy::module_inception)]
 mod graph;
 mod vertex;
+mod compose;
+mod adjoint;
 pub mod phase;
+pub mod scalar;
 
 pub use graph::Graph;
-pub use vertex::{Coords, Vertex, VertexBuilder, VertexType};
+pub use vertex::{Coords, Untyped, Vertex, VertexBuilder, VertexIndex, VertexType};
+use thiserror::Error;
 
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GraphError {
+    #[error("vertex {0} does not exist")] MissingVertex(usize),
+    #[error("edge {0} does not exist")] MissingEdge(usize),
+    #[error("invalid Pauli character '{0}'")] InvalidPauli(char),
+    #[error("qubit {0} out of range")] QubitOutOfRange(usize),
+    #[error("qubit {0} already has a boundary")] BoundaryConflict(usize),
+    #[error("boundary of qubit {0} is not connected")] DisconnectedBoundary(usize),
+}
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-pub enum EdgeType { Simple, Hadamard }
\ No newline at end of file
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EdgeType { Simple, Hadamard }
+
+impl EdgeType {
+    /// Returns the type of a single edge equivalent to `self` followed by `other`.
+    pub fn compose(self, other: EdgeType) -> EdgeType {
+        if self == other { EdgeType::Simple } else { EdgeType::Hadamard }
+    }
+
+    /// Returns the other edge type.
+    pub fn toggled(self) -> EdgeType {
+        match self {
+            EdgeType::Simple => EdgeType::Hadamard,
+            EdgeType::Hadamard => EdgeType::Simple,
+        }
+    }
+}
@@ -1,17 +1,121 @@
+use fraction::ToPrimitive;
 use fraction::Fraction;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Phase of a vertex
+///
+/// A multiple of π plus a linear combination of symbolic variables. The constant part is a
+/// fraction, or a floating-point number for angles with no exact form, and is kept in the range
+/// [0, 2), as are the coefficients of boolean variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Phase {
-    angle: Fraction,
+    angle: Angle,
+    terms: BTreeMap<Variable, Fraction>,
+}
+
+/// Constant part of a [`Phase`], in multiples of π
+#[derive(Debug, Clone, Copy)]
+enum Angle {
+    Exact(Fraction),
+    Float(f64),
+}
+
+/// Symbolic variable of a [`Phase`], named by its LaTeX symbol
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Variable {
+    /// An arbitrary angle `θ`
+    Parameter(String),
+    /// A boolean `b`, contributing `bπ`
+    Boolean(String),
 }
 
 impl Phase {
+    /// Returns the phase `angle`, in multiples of π, converting its binary value exactly.
+    ///
+    /// Values such as `1.0 / 3.0` have no short binary form and give an enormous denominator,
+    /// for those use [`Phase::from_ratio`], [`Phase::approximate`] or [`Phase::float`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `angle` is not finite.
     pub fn new(angle: f64) -> Self {
-        let mut frac = Fraction::from(angle % 2.0);
-        if frac < Fraction::from(0) {
-            frac += Fraction::from(2)
+        assert!(angle.is_finite(), "angle is not finite");
+        Phase::wrap(Fraction::from(angle))
+    }
+
+    /// Returns the phase `angle`, in multiples of π.
+    pub fn from_fraction(angle: Fraction) -> Self {
+        Phase::wrap(angle)
+    }
+
+    /// Returns the phase `numer / denom`, in multiples of π.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `denom` is zero.
+    pub fn from_ratio(numer: i64, denom: u64) -> Self {
+        assert_ne!(denom, 0, "phase denominator is zero");
+        let angle = Fraction::new(numer.unsigned_abs(), denom);
+        Phase::wrap(if numer < 0 { -angle } else { angle })
+    }
+
+    /// Returns the fraction with a denominator of at most `max_denominator` closest to `angle`,
+    /// in multiples of π, found from the continued fraction of `angle`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_denominator` is zero or `angle` is not finite.
+    ///
+    /// Runs in **O(log d)** time, where *d* is `max_denominator`.
+    pub fn approximate(angle: f64, max_denominator: u64) -> Self {
+        assert_ne!(max_denominator, 0, "phase denominator is zero");
+        assert!(angle.is_finite(), "angle is not finite");
+        let angle = wrap_float(angle);
+        // Numerators are at most twice the denominators, so this keeps both within range
+        let max_denominator = max_denominator.min(u64::MAX / 4);
+
+        // Consecutive convergents (p0 / q0, p1 / q1) of the continued fraction
+        let (mut p0, mut q0, mut p1, mut q1) = (0u64, 1u64, 1u64, 0u64);
+        let mut rest = angle;
+        loop {
+            let a = rest.floor() as u64;
+            let Some(q2) = a.checked_mul(q1).and_then(|aq| aq.checked_add(q0)).filter(|&q2| q2 <= max_denominator) else {
+                break;
+            };
+            (p0, q0, p1, q1) = (p1, q1, p0 + a * p1, q2);
+            if p1 as f64 / q1 as f64 == angle || rest == rest.floor() {
+                return Phase::from_fraction(Fraction::new(p1, q1));
+            }
+            rest = 1.0 / (rest - rest.floor());
         }
-        Phase { angle: frac }
+
+        // The best approximation is the last convergent or the semiconvergent before it
+        let k = (max_denominator - q0) / q1;
+        let (semi, convergent) = (Fraction::new(p0 + k * p1, q0 + k * q1), Fraction::new(p1, q1));
+        let distance = |fraction: Fraction| (fraction.to_f64().expect("fraction is finite") - angle).abs();
+        Phase::from_fraction(if distance(convergent) <= distance(semi) { convergent } else { semi })
+    }
+
+    /// Returns the phase `angle`, in multiples of π, kept as a floating-point number.
+    ///
+    /// For angles with no exact form. Adding a floating-point phase to any other phase gives a
+    /// floating-point phase.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `angle` is not finite.
+    pub fn float(angle: f64) -> Self {
+        assert!(angle.is_finite(), "angle is not finite");
+        Phase { angle: Angle::Float(wrap_float(angle)), terms: BTreeMap::new() }
+    }
+
+    /// Wraps `angle` (in multiples of π) into the range [0, 2)
+    fn wrap(angle: Fraction) -> Self {
+        Phase { angle: Angle::Exact(wrap(angle)), terms: BTreeMap::new() }
     }
 
     pub fn zero() -> Self {
@@ -30,9 +134,382 @@ impl Phase {
         Phase::new(-0.5)
     }
 
-    pub fn angle(&self) -> Fraction {
-        self.angle
+    /// Returns the phase `θ` of the angle parameter named `name`.
+    pub fn parameter(name: &str) -> Self {
+        Phase::variable(Variable::Parameter(name.to_owned()))
+    }
+
+    /// Returns the phase `bπ` of the boolean variable named `name`.
+    pub fn boolean(name: &str) -> Self {
+        Phase::variable(Variable::Boolean(name.to_owned()))
+    }
+
+    fn variable(variable: Variable) -> Self {
+        Phase { angle: Angle::Exact(Fraction::from(0)), terms: BTreeMap::from([(variable, Fraction::from(1))]) }
+    }
+
+    /// Returns the constant part of the phase, in multiples of π, or `None` if it is a
+    /// floating-point number.
+    pub fn angle(&self) -> Option<Fraction> {
+        match self.angle {
+            Angle::Exact(angle) => Some(angle),
+            Angle::Float(_) => None,
+        }
+    }
+
+    /// Returns the constant part of the phase as a phase of its own.
+    pub fn constant(&self) -> Phase {
+        Phase { angle: self.angle, terms: BTreeMap::new() }
+    }
+
+    /// Returns the value of the phase in multiples of π, or `None` if it is symbolic.
+    pub fn to_f64(&self) -> Option<f64> {
+        self.is_constant().then(|| self.angle.to_f64())
+    }
+
+    /// Returns true if the constant part of the phase is a fraction.
+    pub fn is_exact(&self) -> bool {
+        matches!(self.angle, Angle::Exact(_))
+    }
+
+    /// Returns the variables of the phase with their coefficients, in order.
+    pub fn terms(&self) -> impl Iterator<Item = (&Variable, Fraction)> {
+        self.terms.iter().map(|(variable, &coefficient)| (variable, coefficient))
+    }
+
+    /// Returns true if the phase has no symbolic variables.
+    pub fn is_constant(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// Returns true if the phase is `0` or `π` for every value of its boolean variables.
+    pub fn is_pauli(&self) -> bool {
+        self.quarter_turns().is_some_and(|quarters| quarters % 4 == 0)
+    }
+
+    /// Returns true if the phase is a multiple of `π/2` for every value of its boolean variables.
+    pub fn is_clifford(&self) -> bool {
+        self.quarter_turns().is_some_and(|quarters| quarters % 2 == 0)
+    }
+
+    /// Returns true if the phase is `±π/2` for every value of its boolean variables.
+    pub fn is_proper_clifford(&self) -> bool {
+        self.quarter_turns().is_some_and(|quarters| quarters % 4 == 2)
+    }
+
+    /// Returns true if the phase is an odd multiple of `π/4` for every value of its boolean
+    /// variables.
+    pub fn is_t_like(&self) -> bool {
+        self.quarter_turns().is_some_and(|quarters| quarters % 2 == 1)
+    }
+
+    /// Returns true if the phase is not always a multiple of `π/2`, which includes every phase
+    /// with a parameter and every floating-point phase.
+    pub fn is_non_clifford(&self) -> bool {
+        !self.is_clifford()
+    }
+
+    /// Returns the constant part of the phase in multiples of `π/4`, if it is an exact multiple
+    /// of `π/4` and every term is a boolean variable times `π`, which only adds `0` or `π`.
+    fn quarter_turns(&self) -> Option<u64> {
+        let integer_booleans = self.terms.iter()
+            .all(|(variable, coefficient)| matches!(variable, Variable::Boolean(_)) && coefficient.denom() == Some(&1));
+        let quarters = self.angle().filter(|_| integer_booleans)? * Fraction::from(4);
+        match quarters.denom() {
+            Some(1) => quarters.numer().copied(),
+            _ => None,
+        }
+    }
+
+    /// Returns the phase with the parameter named `name` replaced by `value`.
+    ///
+    /// Runs in **O(t log t)** time, where *t* is the number of terms of both phases.
+    pub fn substitute(&self, name: &str, value: &Phase) -> Phase {
+        let mut phase = self.clone();
+        match phase.terms.remove(&Variable::Parameter(name.to_owned())) {
+            Some(coefficient) => phase + value.clone().scale(coefficient),
+            None => phase,
+        }
+    }
+
+    /// Returns the phase with the boolean variable named `name` set to `value`.
+    ///
+    /// Runs in **O(log t)** time, where *t* is the number of terms.
+    pub fn assign(&self, name: &str, value: bool) -> Phase {
+        let mut phase = self.clone();
+        if let Some(coefficient) = phase.terms.remove(&Variable::Boolean(name.to_owned())) {
+            if value {
+                phase.angle = phase.angle + Angle::Exact(coefficient);
+            }
+        }
+        phase
+    }
+
+    /// Multiplies the constant part and every coefficient by `factor`.
+    fn scale(self, factor: Fraction) -> Phase {
+        let terms = self.terms
+            .into_iter()
+            .map(|(variable, coefficient)| {
+                let coefficient = coefficient * factor;
+                let coefficient = if let Variable::Boolean(_) = variable { wrap(coefficient) } else { coefficient };
+                (variable, coefficient)
+            })
+            .filter(|&(_, coefficient)| coefficient != Fraction::from(0))
+            .collect();
+        Phase { angle: self.angle * factor, terms }
+    }
+}
+
+impl Variable {
+    pub fn name(&self) -> &str {
+        match self {
+            Variable::Parameter(name) | Variable::Boolean(name) => name,
+        }
+    }
+}
+
+impl Angle {
+    fn to_f64(self) -> f64 {
+        match self {
+            Angle::Exact(angle) => angle.to_f64().expect("phase is finite"),
+            Angle::Float(angle) => angle,
+        }
+    }
+}
+
+/// Wraps `angle` (in multiples of π) into the range [0, 2)
+fn wrap(angle: Fraction) -> Fraction {
+    let mut frac = angle % Fraction::from(2);
+    if frac < Fraction::from(0) {
+        frac += Fraction::from(2)
+    }
+    frac
+}
+
+/// Wraps `angle` (in multiples of π) into the range [0, 2), without negative zero
+fn wrap_float(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(2.0);
+    if angle >= 2.0 { 0.0 } else { angle + 0.0 }
+}
+
+impl PartialEq for Angle {
+    /// Exact angles are never equal to floating-point ones.
+    fn eq(&self, other: &Angle) -> bool {
+        match (self, other) {
+            (Angle::Exact(a), Angle::Exact(b)) => a == b,
+            (Angle::Float(a), Angle::Float(b)) => a.to_bits() == b.to_bits(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Angle {}
+
+impl Add for Angle {
+    type Output = Angle;
+
+    fn add(self, other: Angle) -> Angle {
+        match (self, other) {
+            (Angle::Exact(a), Angle::Exact(b)) => Angle::Exact(wrap(a + b)),
+            _ => Angle::Float(wrap_float(self.to_f64() + other.to_f64())),
+        }
+    }
+}
+
+impl Mul<Fraction> for Angle {
+    type Output = Angle;
+
+    fn mul(self, factor: Fraction) -> Angle {
+        match self {
+            Angle::Exact(angle) => Angle::Exact(wrap(angle * factor)),
+            Angle::Float(angle) => Angle::Float(wrap_float(angle * factor.to_f64().expect("factor is finite"))),
+        }
+    }
+}
+
+impl From<Fraction> for Phase {
+    fn from(angle: Fraction) -> Self {
+        Phase::from_fraction(angle)
+    }
+}
+
+impl Add for Phase {
+    type Output = Phase;
+
+    /// Adds the constant parts and the coefficients of each variable, dropping variables whose
+    /// coefficients cancel.
+    fn add(self, other: Phase) -> Phase {
+        let mut terms = self.terms;
+        for (variable, coefficient) in other.terms {
+            let sum = *terms.get(&variable).unwrap_or(&Fraction::from(0)) + coefficient;
+            let sum = if let Variable::Boolean(_) = variable { wrap(sum) } else { sum };
+            if sum == Fraction::from(0) {
+                terms.remove(&variable);
+            } else {
+                terms.insert(variable, sum);
+            }
+        }
+        Phase { angle: self.angle + other.angle, terms }
+    }
+}
+
+impl Sub for Phase {
+    type Output = Phase;
+
+    fn sub(self, other: Phase) -> Phase {
+        self + -other
+    }
+}
+
+impl Neg for Phase {
+    type Output = Phase;
+
+    fn neg(self) -> Phase {
+        self.scale(Fraction::from(-1))
+    }
+}
+
+impl Mul<i64> for Phase {
+    type Output = Phase;
+
+    fn mul(self, factor: i64) -> Phase {
+        self.scale(Fraction::from(factor))
+    }
+}
+
+impl fmt::Display for Phase {
+    /// Writes the variables of the phase in order followed by its constant part, as in
+    /// `2\theta - b*pi + 3pi/4`, or `0` for the zero phase
+    ///
+    /// A floating-point constant part is written as a decimal, which parses back as the closest
+    /// fraction with a 64-bit denominator.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let variables = self.terms().map(|(variable, coefficient)| match variable {
+            Variable::Parameter(name) => (coefficient, name.clone()),
+            Variable::Boolean(name) => (coefficient, format!("{name}*pi")),
+        });
+        let constant = self.angle().map(|angle| (angle, "pi".to_owned())).filter(|&(angle, _)| angle != Fraction::from(0));
+        let mut empty = true;
+        for (coefficient, symbol) in variables.chain(constant) {
+            match (empty, coefficient < Fraction::from(0)) {
+                (true, false) => {}
+                (true, true) => write!(f, "-")?,
+                (false, false) => write!(f, " + ")?,
+                (false, true) => write!(f, " - ")?,
+            }
+            empty = false;
+            match (coefficient.numer(), coefficient.denom()) {
+                (Some(1), Some(1)) => write!(f, "{symbol}")?,
+                (Some(n), Some(1)) => write!(f, "{n}{symbol}")?,
+                (Some(1), Some(d)) => write!(f, "{symbol}/{d}")?,
+                (Some(n), Some(d)) => write!(f, "{n}{symbol}/{d}")?,
+                _ => write!(f, "{coefficient}")?,
+            }
+        }
+        match self.angle {
+            Angle::Float(angle) if angle != 0.0 => write!(f, "{}{angle}", if empty { "" } else { " + " }),
+            _ if empty => write!(f, "0"),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParsePhaseError {
+    #[error("phase is empty")] Empty,
+    #[error("invalid phase term \"{0}\"")] InvalidTerm(String),
+    #[error("phase term \"{0}\" has a zero denominator")] ZeroDenominator(String),
+}
+
+impl FromStr for Phase {
+    type Err = ParsePhaseError;
+
+    /// Parses a sum of terms such as `3pi/4`, `-π/8`, `\theta/2 + b*pi` or `0.25`.
+    ///
+    /// A term is an optional integer coefficient, a symbol and an optional integer denominator.
+    /// The symbol is `pi` (or `π`), the name of a parameter, or the name of a boolean variable
+    /// followed by `*pi`. A term that is a plain decimal number is a multiple of π.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParsePhaseError::Empty);
+        }
+
+        let mut phase = Phase::zero();
+        let mut start = 0;
+        for (end, c) in s.char_indices().skip(1).chain([(s.len(), '+')]) {
+            if c == '+' || c == '-' {
+                phase = phase + parse_term(s[start..end].trim())?;
+                start = end;
+            }
+        }
+        Ok(phase)
+    }
+}
+
+/// Parses a single term of a phase, with its sign.
+fn parse_term(term: &str) -> Result<Phase, ParsePhaseError> {
+    let invalid = || ParsePhaseError::InvalidTerm(term.to_owned());
+    let (sign, unsigned) = match term.as_bytes().first() {
+        Some(b'-') => (-1, term[1..].trim_start()),
+        Some(b'+') => (1, term[1..].trim_start()),
+        _ => (1, term),
+    };
+    let (body, denom) = match unsigned.split_once('/') {
+        Some((body, denom)) => (body, denom.parse::<u64>().map_err(|_| invalid())?),
+        None => (unsigned, 1),
+    };
+    if denom == 0 {
+        return Err(ParsePhaseError::ZeroDenominator(term.to_owned()));
+    }
+    let factor = Fraction::from(sign) / Fraction::from(denom);
+
+    if let Some(angle) = parse_decimal(body) {
+        return Ok(Phase::from_fraction(angle * factor));
+    }
+    let digits = body.len() - body.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let (coefficient, symbol) = body.split_at(digits);
+    let coefficient = if digits == 0 { 1 } else { coefficient.parse::<u64>().map_err(|_| invalid())? };
+    let symbol = symbol.strip_prefix('*').unwrap_or(symbol);
+    let phase = match symbol {
+        "pi" | "π" => Phase { angle: Angle::Exact(Fraction::from(1)), terms: BTreeMap::new() },
+        _ => match symbol.strip_suffix("*pi").or_else(|| symbol.strip_suffix("*π")) {
+            Some(name) if is_name(name) => Phase::boolean(name),
+            None if is_name(symbol) => Phase::parameter(symbol),
+            _ => return Err(invalid()),
+        },
+    };
+    Ok(phase.scale(Fraction::from(coefficient) * factor))
+}
+
+/// Parses a decimal number such as `0.25`, exactly unless its digits overflow 64 bits, in which
+/// case its fractional part is replaced by the closest fraction that fits.
+fn parse_decimal(body: &str) -> Option<Fraction> {
+    let (whole, decimals) = body.split_once('.').unwrap_or((body, ""));
+    let all_digits = |digits: &str| digits.chars().all(|c| c.is_ascii_digit());
+    if whole.len() + decimals.len() == 0 || !all_digits(whole) || !all_digits(decimals) {
+        return None;
+    }
+    let numer = format!("{whole}{decimals}").parse::<u64>().ok();
+    let denom = 10u64.checked_pow(decimals.len() as u32);
+    if let (Some(numer), Some(denom)) = (numer, denom) {
+        return Some(Fraction::new(numer, denom));
     }
+    let whole = if whole.is_empty() { 0 } else { whole.parse::<u64>().ok()? };
+    let fractional = format!("0.{decimals}").parse::<f64>().ok()?;
+    // Bounding the denominator keeps the numerator of the sum within range
+    let max_denominator = u64::MAX / whole.saturating_add(2);
+    let fractional = Phase::approximate(fractional, max_denominator).angle().expect("approximation is exact");
+    Some(Fraction::from(whole) + fractional)
+}
+
+/// Returns true if `name` can name a variable: it is not empty, does not start with a digit and
+/// holds only letters, digits, underscores and backslashes.
+fn is_name(name: &str) -> bool {
+    name.chars().next().is_some_and(|c| !c.is_ascii_digit())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '\\')
+        && name != "pi"
+        && name != "π"
 }
 
 #[cfg(test)]
@@ -49,74 +526,249 @@ mod tests {
     #[test]
     fn test_phase_from_zero() {
         let phase = Phase::new(0.0);
-        assert_eq!(phase.angle(), Fraction::from(0));
+        assert_eq!(phase.angle(), Some(Fraction::from(0)));
     }
 
     #[test]
     fn test_phase_from_positive_angle_under_two_pi() {
         let phase = Phase::new(1.25);
-        assert_eq!(phase.angle(), Fraction::from(1.25 % 2.0));
+        assert_eq!(phase.angle(), Some(Fraction::from(1.25 % 2.0)));
     }
 
     #[test]
     fn test_phase_wraps_around_two_pi() {
         let phase = Phase::new(4.5);
-        assert_eq!(phase.angle(), Fraction::from(0.5));
+        assert_eq!(phase.angle(), Some(Fraction::from(0.5)));
     }
 
     #[test]
     fn test_phase_from_negative_angle() {
         let phase = Phase::new(-0.5);
-        assert_eq!(phase.angle(), Fraction::from(1.5));
+        assert_eq!(phase.angle(), Some(Fraction::from(1.5)));
     }
 
     #[test]
     fn test_phase_from_exactly_two_pi_wraps_to_zero() {
         let phase = Phase::new(2.0);
-        assert_eq!(phase.angle(), Fraction::from(0));
+        assert_eq!(phase.angle(), Some(Fraction::from(0)));
     }
 
     #[test]
     fn test_phase_from_multiple_of_two_pi_wraps_to_zero() {
         let phase = Phase::new(6.0);
-        assert_eq!(phase.angle(), Fraction::from(0));
+        assert_eq!(phase.angle(), Some(Fraction::from(0)));
+    }
+
+    #[test]
+    fn test_phase_negation() {
+        assert_eq!(-Phase::plus(), Phase::minus());
+        assert_eq!(-Phase::one(), Phase::one());
+        assert_eq!(-Phase::zero(), Phase::zero());
+    }
+
+    #[test]
+    fn test_phase_addition_wraps() {
+        assert_eq!(Phase::one() + Phase::one(), Phase::zero());
+        assert_eq!(Phase::minus() + Phase::one(), Phase::plus());
+    }
+
+    #[test]
+    fn test_symbolic_phases_add_and_cancel() {
+        let theta = Phase::parameter("\\theta");
+        let sum = theta.clone() + Phase::plus() + theta.clone() + Phase::parameter("\\phi");
+        assert!(!sum.is_constant());
+        assert_eq!(sum.angle(), Some(frac!(1, 2)));
+        assert_eq!(sum.terms().map(|(variable, _)| variable.name()).collect::<Vec<_>>(), ["\\phi", "\\theta"]);
+        assert_eq!(sum.terms().map(|(_, coefficient)| coefficient).collect::<Vec<_>>(), [frac!(1, 1), frac!(2, 1)]);
+        assert_eq!(theta.clone() + -theta, Phase::zero());
+    }
+
+    #[test]
+    fn test_boolean_coefficients_wrap() {
+        let b = Phase::boolean("b");
+        assert_eq!(b.clone() + b.clone(), Phase::zero());
+        assert_eq!(-b.clone(), b);
+    }
+
+    #[test]
+    fn test_substitute_and_assign() {
+        let phase = -Phase::parameter("\\theta") + Phase::boolean("b") + Phase::plus();
+        let substituted = phase.substitute("\\theta", &(Phase::parameter("\\phi") + Phase::one()));
+        assert_eq!(substituted, -Phase::parameter("\\phi") + Phase::boolean("b") + Phase::minus());
+        assert_eq!(substituted.assign("b", true).substitute("\\phi", &Phase::plus()), Phase::zero());
+        assert_eq!(phase.assign("b", false).assign("c", true), -Phase::parameter("\\theta") + Phase::plus());
+    }
+
+    #[test]
+    fn test_phase_subtraction_and_integer_multiples() {
+        assert_eq!(Phase::plus() - Phase::one(), Phase::minus());
+        assert_eq!(Phase::from_ratio(1, 4) * 6, Phase::minus());
+        assert_eq!(Phase::from_ratio(1, 4) * -3, Phase::from_ratio(5, 4));
+        assert_eq!(Phase::boolean("b") * 3, Phase::boolean("b"));
+        assert_eq!((Phase::parameter("t") - Phase::parameter("t") * 2) * 2, Phase::parameter("t") * -2);
+    }
+
+    #[test]
+    fn test_exact_construction() {
+        assert_eq!(Phase::from_ratio(1, 3).angle(), Some(frac!(1, 3)));
+        assert_eq!(Phase::from_ratio(-1, 3).angle(), Some(frac!(5, 3)));
+        assert_eq!(Phase::from_fraction(frac!(7, 3)).angle(), Some(frac!(1, 3)));
+        assert_eq!(Phase::from(frac!(1, 2)), Phase::plus());
+    }
+
+    #[test]
+    fn test_approximate_snaps_to_small_denominators() {
+        assert_eq!(Phase::approximate(1.0 / 3.0, 1000), Phase::from_ratio(1, 3));
+        assert_eq!(Phase::approximate(0.33, 10), Phase::from_ratio(1, 3));
+        assert_eq!(Phase::approximate(-0.25, 8), Phase::from_ratio(7, 4));
+        assert_eq!(Phase::approximate(1.9999, 8), Phase::zero());
+        assert_eq!(Phase::approximate(0.4, 1), Phase::zero());
+        // The best approximation with a denominator of at most 100 is a semiconvergent
+        assert_eq!(Phase::approximate(std::f64::consts::PI, 100), Phase::from_ratio(311, 99));
+        assert_eq!(Phase::approximate(0.375, u64::MAX), Phase::from_ratio(3, 8));
+    }
+
+    #[test]
+    #[should_panic(expected = "angle is not finite")]
+    fn test_float_rejects_non_finite_angles() {
+        Phase::float(f64::NAN);
+    }
+
+    #[test]
+    #[should_panic(expected = "angle is not finite")]
+    fn test_new_rejects_non_finite_angles() {
+        Phase::new(f64::INFINITY);
+    }
+
+    #[test]
+    fn test_float_phases() {
+        let phase = Phase::float(2.25);
+        assert!(!phase.is_exact());
+        assert_eq!(phase.angle(), None);
+        assert_eq!(phase.to_f64(), Some(0.25));
+        assert_eq!(phase, Phase::float(-1.75));
+        assert_ne!(phase, Phase::from_ratio(1, 4));
+
+        let sum = phase.clone() + Phase::parameter("t") + Phase::one();
+        assert!(!sum.is_exact());
+        assert_eq!(sum.to_f64(), None);
+        assert_eq!(sum.constant(), Phase::float(1.25));
+        assert_eq!((-phase).to_f64(), Some(1.75));
+        assert_eq!(Phase::float(0.5).to_string(), "0.5");
+        assert_eq!(sum.to_tex().unwrap(), "$t + 1.250\\pi$");
+    }
+
+    #[test]
+    fn test_classification() {
+        let classes = |phase: Phase| {
+            [phase.is_pauli(), phase.is_clifford(), phase.is_proper_clifford(), phase.is_t_like(), phase.is_non_clifford()]
+        };
+        assert_eq!(classes(Phase::zero()), [true, true, false, false, false]);
+        assert_eq!(classes(Phase::one()), [true, true, false, false, false]);
+        assert_eq!(classes(Phase::minus()), [false, true, true, false, false]);
+        assert_eq!(classes(Phase::from_ratio(7, 4)), [false, false, false, true, true]);
+        assert_eq!(classes(Phase::from_ratio(1, 8)), [false, false, false, false, true]);
+        assert_eq!(classes(Phase::float(0.5)), [false, false, false, false, true]);
+        assert_eq!(classes(Phase::boolean("b")), [true, true, false, false, false]);
+        assert_eq!(classes(Phase::boolean("b") + Phase::plus()), [false, true, true, false, false]);
+        assert_eq!(classes(Phase::boolean("b").scale(frac!(1, 2))), [false, false, false, false, true]);
+        assert_eq!(classes(Phase::parameter("t")), [false, false, false, false, true]);
+    }
+
+    #[test]
+    fn test_parse_constants() {
+        assert_eq!("3pi/4".parse(), Ok(Phase::from_ratio(3, 4)));
+        assert_eq!("-π/8".parse(), Ok(Phase::from_ratio(-1, 8)));
+        assert_eq!("0.25".parse(), Ok(Phase::from_ratio(1, 4)));
+        assert_eq!("pi".parse(), Ok(Phase::one()));
+        assert_eq!(" 3*pi/2 ".parse(), Ok(Phase::minus()));
+        assert_eq!("pi/4 - 3pi/4".parse(), Ok(Phase::minus()));
+        assert_eq!("1/3".parse(), Ok(Phase::from_ratio(1, 3)));
+        assert_eq!("0".parse(), Ok(Phase::zero()));
+    }
+
+    #[test]
+    fn test_parse_symbolic() {
+        let expected = Phase::parameter("\\theta") * 2 - Phase::boolean("b") + Phase::from_ratio(1, 4);
+        assert_eq!("2\\theta - b*pi + pi/4".parse(), Ok(expected));
+        assert_eq!("theta/2".parse(), Ok(Phase::parameter("theta").scale(frac!(1, 2))));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<Phase>(), Err(ParsePhaseError::Empty));
+        assert_eq!("pi/0".parse::<Phase>(), Err(ParsePhaseError::ZeroDenominator("pi/0".to_owned())));
+        assert_eq!("pi + 2x y".parse::<Phase>(), Err(ParsePhaseError::InvalidTerm("+ 2x y".to_owned())));
+        assert!("1.2.3".parse::<Phase>().is_err());
+        assert!("pi/x".parse::<Phase>().is_err());
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        assert_eq!(Phase::zero().to_string(), "0");
+        assert_eq!(Phase::one().to_string(), "pi");
+        assert_eq!(Phase::from_ratio(3, 4).to_string(), "3pi/4");
+        assert_eq!(Phase::minus().to_string(), "3pi/2");
+        let phase = Phase::parameter("\\theta") * -3 + Phase::parameter("\\phi").scale(frac!(1, 2))
+            + Phase::boolean("b") + Phase::from_ratio(1, 8);
+        assert_eq!(phase.to_string(), "\\phi/2 - 3\\theta + b*pi + pi/8");
+        assert_eq!(phase.to_string().parse(), Ok(phase));
+
+        for angle in [0.00012345678901234567, 3e-20, 1e-300] {
+            let phase = Phase::float(angle);
+            assert!(phase.to_string().split_once('.').unwrap().1.len() > 19);
+            let parsed: Phase = phase.to_string().parse().unwrap();
+            assert!(parsed.is_exact());
+            assert!((parsed.to_f64().unwrap() - angle).abs() < 1e-18, "{phase}");
+        }
     }
 
     // Phase::to_latex()
 
     #[test]
     fn test_to_latex_zero() {
-        let phase = Phase { angle: Fraction::from(0) };
+        let phase = Phase { angle: Angle::Exact(Fraction::from(0)), ..Phase::zero() };
         assert_eq!(phase.to_tex().unwrap(), "");
     }
 
     #[test]
     fn test_to_latex_pi() {
-        let phase = Phase { angle: Fraction::from(1) };
+        let phase = Phase { angle: Angle::Exact(Fraction::from(1)), ..Phase::zero() };
         assert_eq!(phase.to_tex().unwrap(), "$\\pi$");
     }
 
     #[test]
     fn test_to_latex_half_pi() {
-        let phase = Phase { angle: frac!(1, 2) };
+        let phase = Phase { angle: Angle::Exact(frac!(1, 2)), ..Phase::zero() };
         assert_eq!(phase.to_tex().unwrap(), "$\\frac{\\pi}{2}$");
     }
 
     #[test]
     fn test_to_latex_three_halves_pi() {
-        let phase = Phase { angle: frac!(3, 2) };
+        let phase = Phase { angle: Angle::Exact(frac!(3, 2)), ..Phase::zero() };
         assert_eq!(phase.to_tex().unwrap(), "$\\frac{3\\pi}{2}$");
     }
 
     #[test]
     fn test_to_latex_negative_half_pi() {
-        let phase = Phase { angle: frac!(-1, 2) };
+        let phase = Phase { angle: Angle::Exact(frac!(-1, 2)), ..Phase::zero() };
         assert_eq!(phase.to_tex().unwrap(), "$\\frac{\\pi}{2}$");
     }
 
+    #[test]
+    fn test_to_latex_symbolic() {
+        let phase = Phase::parameter("\\theta") + Phase::new(0.25);
+        assert_eq!(phase.to_tex().unwrap(), "$\\theta + \\frac{\\pi}{4}$");
+        let phase = -Phase::parameter("\\theta") + Phase::parameter("\\phi") + Phase::parameter("\\phi");
+        assert_eq!(phase.to_tex().unwrap(), "$2\\phi - \\theta$");
+        let phase = -(Phase::parameter("\\theta") + Phase::parameter("\\theta") + Phase::parameter("\\theta"));
+        assert_eq!(phase.to_tex().unwrap(), "$-3\\theta$");
+        assert_eq!((Phase::boolean("b") + Phase::one()).to_tex().unwrap(), "$b\\pi + \\pi$");
+    }
+
     #[test]
     fn test_to_latex_fraction_with_large_denominator() {
-        let phase = Phase { angle: frac!(1, 8) };
+        let phase = Phase { angle: Angle::Exact(frac!(1, 8)), ..Phase::zero() };
         assert_eq!(phase.to_tex().unwrap(), "$\\frac{\\pi}{8}$");
     }
 }
@@ -0,0 +1,136 @@
+use crate::graph::phase::Phase;
+use num_complex::Complex64;
+use std::f64::consts::{PI, SQRT_2};
+use std::ops::{Mul, MulAssign};
+
+/// Global scalar of a diagram
+///
+/// Stored exactly as a power of √2 times a phase, with an optional floating-point factor for
+/// scalars that have no exact form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scalar {
+    sqrt2_power: i32,
+    phase: Phase,
+    factor: Option<Complex64>,
+}
+
+impl Scalar {
+    pub fn new(sqrt2_power: i32, phase: Phase) -> Self {
+        Scalar { sqrt2_power, phase, factor: None }
+    }
+
+    pub fn one() -> Self {
+        Scalar::new(0, Phase::zero())
+    }
+
+    /// Returns the scalar `value`, kept as a floating-point factor.
+    pub fn from_complex(value: Complex64) -> Self {
+        Scalar { factor: Some(value), ..Scalar::one() }
+    }
+
+    pub fn sqrt2_power(&self) -> i32 {
+        self.sqrt2_power
+    }
+
+    pub fn phase(&self) -> &Phase {
+        &self.phase
+    }
+
+    pub fn factor(&self) -> Option<Complex64> {
+        self.factor
+    }
+
+    /// Returns true if the scalar has no floating-point factor.
+    pub fn is_exact(&self) -> bool {
+        self.factor.is_none()
+    }
+
+    /// Multiplies the scalar by √2 raised to `power`.
+    pub fn mul_sqrt2_power(&mut self, power: i32) {
+        self.sqrt2_power += power;
+    }
+
+    /// Multiplies the scalar by `e^(i phase)`.
+    pub fn mul_phase(&mut self, phase: Phase) {
+        self.phase = self.phase.clone() + phase;
+    }
+
+    /// Multiplies the scalar by `factor`, which is kept as a floating-point factor.
+    pub fn mul_factor(&mut self, factor: Complex64) {
+        self.factor = Some(self.factor.unwrap_or(Complex64::ONE) * factor);
+    }
+
+    /// Returns the complex conjugate of the scalar.
+    pub fn conjugate(&self) -> Scalar {
+        Scalar { sqrt2_power: self.sqrt2_power, phase: -self.phase.clone(), factor: self.factor.map(|factor| factor.conj()) }
+    }
+
+    /// Returns the multiplicative inverse of the scalar.
+    ///
+    /// A zero floating-point factor has no inverse and gives a non-finite one.
+    pub fn inverse(&self) -> Scalar {
+        Scalar { sqrt2_power: -self.sqrt2_power, phase: -self.phase.clone(), factor: self.factor.map(|factor| factor.inv()) }
+    }
+
+    /// Returns the value of the scalar as a complex number.
+    ///
+    /// Panics if the phase is symbolic.
+    pub fn to_complex(&self) -> Complex64 {
+        let angle = self.phase.to_f64().expect("scalar phase is constant") * PI;
+        let value = Complex64::from_polar(SQRT_2.powi(self.sqrt2_power), angle);
+        self.factor.map_or(value, |factor| value * factor)
+    }
+}
+
+impl Default for Scalar {
+    fn default() -> Self {
+        Scalar::one()
+    }
+}
+
+impl Mul for Scalar {
+    type Output = Scalar;
+
+    fn mul(mut self, other: Scalar) -> Scalar {
+        self *= other;
+        self
+    }
+}
+
+impl MulAssign for Scalar {
+    fn mul_assign(&mut self, other: Scalar) {
+        self.mul_sqrt2_power(other.sqrt2_power);
+        self.mul_phase(other.phase);
+        if let Some(factor) = other.factor {
+            self.mul_factor(factor);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Scalar;
+    use crate::graph::phase::Phase;
+    use num_complex::Complex64;
+
+    #[test]
+    fn exact_scalars_multiply_exactly() {
+        let scalar = Scalar::new(3, Phase::plus()) * Scalar::new(-1, Phase::one());
+        assert_eq!(scalar, Scalar::new(2, Phase::minus()));
+        assert!(scalar.is_exact());
+        assert!((scalar.to_complex() - Complex64::new(0.0, -2.0)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn factors_and_conjugation() {
+        let mut scalar = Scalar::new(1, Phase::new(0.25));
+        scalar.mul_factor(Complex64::new(0.0, 0.5));
+        assert!(!scalar.is_exact());
+
+        let value = scalar.to_complex();
+        assert!((scalar.conjugate().to_complex() - value.conj()).norm() < 1e-12);
+        assert!(((scalar.clone() * scalar.conjugate()).to_complex() - value.norm_sqr()).norm() < 1e-12);
+        assert!(((scalar.clone() * scalar.inverse()).to_complex() - 1.0).norm() < 1e-12);
+    }
+}
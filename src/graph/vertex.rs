@@ -4,7 +4,18 @@ use petgraph::graph::NodeIndex;
 pub type VertexIndex = NodeIndex;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
-pub enum VertexType { Z, X, Y, H }
+pub enum VertexType {
+    Z,
+    X,
+    /// Y spider, whose legs are not interchangeable
+    ///
+    /// Each leg is oriented by the edge it lies on: where the spider is the stored `source` of the
+    /// edge the leg is an output in the Y basis, and where it is the stored `target` an input.
+    /// Anything that moves or replaces an edge of a Y spider must keep its stored direction.
+    Y,
+    H,
+    B,
+}
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Coords {
@@ -28,7 +39,7 @@ impl Vertex {
 
     /// Returns vertex_type
     pub fn phase(&self) -> Phase {
-        self.phase
+        self.phase.clone()
     }
 
     /// Returns x coordinate
@@ -55,6 +66,16 @@ impl Vertex {
     pub fn is_positioned(&self) -> bool {
         self.coords.is_some()
     }
+
+    /// Returns true if Vertex is a Z or X spider
+    pub fn is_spider(&self) -> bool {
+        matches!(self.vertex_type, VertexType::Z | VertexType::X)
+    }
+
+    /// Returns true if Vertex is an input or output boundary
+    pub fn is_boundary(&self) -> bool {
+        self.vertex_type == VertexType::B
+    }
 }
 
 /// Constructors
@@ -82,39 +103,63 @@ impl Vertex {
             coords: None,
         }
     }
+
+    pub fn b() -> Self {
+        Self {
+            vertex_type: VertexType::B,
+            phase: Phase::zero(),
+            coords: None,
+        }
+    }
 }
 
 
-// todo - use the typestate builder pattern here
+/// Builder for [`Vertex`]
+///
+/// The type parameter records whether a vertex type has been chosen: it starts as [`Untyped`]
+/// and becomes [`VertexType`] once one is set, and only then can the vertex be built.
 #[derive(Debug, Clone)]
-pub struct VertexBuilder {
-    vertex_type: Option<VertexType>,
+pub struct VertexBuilder<T = Untyped> {
+    vertex_type: T,
     phase: Option<Phase>,
     coords: Option<Coords>,
 }
 
+/// State of a [`VertexBuilder`] that has no vertex type yet
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Untyped;
+
+impl Default for VertexBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl VertexBuilder {
     pub fn new() -> Self {
         Self {
-            vertex_type: None,
+            vertex_type: Untyped,
             phase: None,
             coords: None,
         }
     }
+}
 
+impl VertexBuilder<VertexType> {
     /// Builder: build Vertex
     pub fn build(self) -> Vertex {
         Vertex {
-            vertex_type: self.vertex_type.expect("vertex_type not set"),
+            vertex_type: self.vertex_type,
             phase: self.phase.unwrap_or(Phase::zero()),
             coords: self.coords,
         }
     }
+}
 
+impl<T> VertexBuilder<T> {
     /// Builder: set vertex_type
-    pub fn vertex_type(mut self, vertex_type: VertexType) -> Self {
-        self.vertex_type = Some(vertex_type);
-        self
+    pub fn vertex_type(self, vertex_type: VertexType) -> VertexBuilder<VertexType> {
+        VertexBuilder { vertex_type, phase: self.phase, coords: self.coords }
     }
 
     /// Builder: set phase
@@ -128,64 +173,100 @@ impl VertexBuilder {
         self.coords = Some(Coords {x, y});
         self
     }
+}
 
+impl VertexBuilder {
     /// Builder: convenience constructor
-    pub fn z() -> Self {
+    pub fn z() -> VertexBuilder<VertexType> {
         Self::new().vertex_type(VertexType::Z)
     }
 
     /// Builder: convenience constructor
-    pub fn x() -> Self {
+    pub fn x() -> VertexBuilder<VertexType> {
         Self::new().vertex_type(VertexType::X)
     }
 
     /// Builder: convenience constructor
-    pub fn y() -> Self {
+    pub fn y() -> VertexBuilder<VertexType> {
         Self::new().vertex_type(VertexType::Y)
     }
 
     /// Builder: convenience constructor
-    pub fn z_pauli() -> Self {
+    pub fn b() -> VertexBuilder<VertexType> {
+        Self::new().vertex_type(VertexType::B)
+    }
+
+    /// Builder: convenience constructor
+    pub fn z_pauli() -> VertexBuilder<VertexType> {
         Self::z().phase(Phase::one())
     }
 
     /// Builder: convenience constructor
-    pub fn x_pauli() -> Self {
+    pub fn x_pauli() -> VertexBuilder<VertexType> {
         Self::x().phase(Phase::one())
     }
 
     /// Builder: convenience constructor
-    pub fn y_pauli() -> Self {
+    pub fn y_pauli() -> VertexBuilder<VertexType> {
         Self::y().phase(Phase::one())
     }
 
     /// Builder: convenience constructor
-    pub fn z_plus() -> Self {
+    pub fn z_plus() -> VertexBuilder<VertexType> {
         Self::z().phase(Phase::plus())
     }
 
     /// Builder: convenience constructor
-    pub fn z_minus() -> Self {
+    pub fn z_minus() -> VertexBuilder<VertexType> {
         Self::z().phase(Phase::minus())
     }
 
     /// Builder: convenience constructor
-    pub fn x_plus() -> Self {
+    pub fn x_plus() -> VertexBuilder<VertexType> {
         Self::x().phase(Phase::plus())
     }
 
     /// Builder: convenience constructor
-    pub fn x_minus() -> Self {
+    pub fn x_minus() -> VertexBuilder<VertexType> {
         Self::x().phase(Phase::minus())
     }
 
     /// Builder: convenience constructor
-    pub fn y_plus() -> Self {
+    pub fn y_plus() -> VertexBuilder<VertexType> {
         Self::y().phase(Phase::plus())
     }
 
     /// Builder: convenience constructor
-    pub fn y_minus() -> Self {
+    pub fn y_minus() -> VertexBuilder<VertexType> {
         Self::y().phase(Phase::minus())
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::VertexBuilder;
+    use crate::graph::phase::Phase;
+    use crate::graph::VertexType;
+
+    #[test]
+    fn convenience_constructors_have_clifford_phases() {
+        for builder in [VertexBuilder::z_pauli(), VertexBuilder::x_pauli(), VertexBuilder::y_pauli()] {
+            assert!(builder.build().phase().is_pauli());
+        }
+        for builder in [VertexBuilder::z_plus(), VertexBuilder::z_minus(), VertexBuilder::x_plus(), VertexBuilder::y_minus()] {
+            let phase = builder.build().phase();
+            assert!(phase.is_proper_clifford() && !phase.is_pauli());
+        }
+        assert!(!VertexBuilder::z().build().phase().is_non_clifford());
+    }
+
+    #[test]
+    fn vertex_type_can_be_set_after_other_fields() {
+        let vertex = VertexBuilder::new().coords(1.0, 2.0).phase(Phase::one()).vertex_type(VertexType::H).build();
+        assert_eq!(vertex.vertex_type(), VertexType::H);
+        assert_eq!(vertex.phase(), Phase::one());
+        assert!(vertex.is_positioned());
+        assert_eq!(VertexBuilder::z_plus().vertex_type(VertexType::X).build().phase(), Phase::plus());
+    }
+}
@@ -1,3 +1,5 @@
 pub mod graph;
 pub mod export;
-mod builders;
+pub mod rewrite;
+pub mod builders;
+pub mod tensor;
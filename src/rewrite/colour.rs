@@ -0,0 +1,271 @@
+use crate::graph::phase::Phase;
+use crate::graph::{Coords, EdgeType, Graph, Vertex, VertexBuilder, VertexIndex, VertexType};
+use crate::rewrite::identity::bypass;
+use crate::rewrite::{ColourChange, GraphLike, Hopf, RewriteError, SpiderFusion};
+use petgraph::prelude::{EdgeIndex, EdgeRef};
+use std::collections::HashSet;
+
+impl ColourChange for Graph {
+    /// Rewrite: changes the colour of spider `v`
+    ///
+    /// A Z spider becomes an X spider and vice versa, keeping its phase. Every edge to another
+    /// vertex is toggled between `Simple` and `Hadamard`, while self-loops are left unchanged.
+    fn change_colour(&mut self, v: VertexIndex) -> Result<(), RewriteError> {
+        let vertex = self.vertex_mut(v).ok_or(RewriteError::MissingVertex(v.index()))?;
+        let colour = match vertex.vertex_type() {
+            VertexType::Z => VertexType::X,
+            VertexType::X => VertexType::Z,
+            _ => return Err(RewriteError::NotSpider(v.index())),
+        };
+        vertex.set_vertex_type(colour);
+
+        let edges: Vec<EdgeIndex> = self.incident_edges(v)
+            .filter(|edge| edge.target() != v)
+            .map(|edge| edge.id())
+            .collect();
+        for edge in edges {
+            let edge_type = self.edge_mut(edge).expect("edge exists");
+            *edge_type = edge_type.toggled();
+        }
+        Ok(())
+    }
+}
+
+impl GraphLike for Graph {
+    /// Rewrite: converts the diagram into graph-like form
+    ///
+    /// Hadamard vertices with two edges become Hadamard edges, Y spiders are expanded into Z
+    /// spiders and X spiders change colour, after which adjacent spiders are fused. Self-loops and
+    /// pairs of parallel Hadamard edges are then removed, and every boundary is attached to a Z
+    /// spider.
+    ///
+    /// Hadamard vertices with any other number of edges are left in place.
+    fn to_graph_like(&mut self) {
+        let vertices: Vec<(VertexIndex, VertexType)> = self.enumerate_vertices()
+            .map(|(index, vertex)| (index, vertex.vertex_type()))
+            .collect();
+
+        for (index, vertex_type) in vertices {
+            match vertex_type {
+                VertexType::H => remove_hadamard_vertex(self, index),
+                VertexType::Y => expand_y(self, index),
+                VertexType::X => self.change_colour(index).expect("vertex is a spider"),
+                VertexType::Z | VertexType::B => {}
+            }
+        }
+
+        self.fuse_all();
+        self.normalise_edges();
+        attach_boundaries(self);
+    }
+
+    /// Returns true if every vertex is a boundary or a Z spider, spiders are only joined by
+    /// `Hadamard` edges, there are no self-loops or parallel edges, and every boundary is
+    /// attached to exactly one Z spider.
+    fn is_graph_like(&self) -> bool {
+        let mut seen = HashSet::new();
+        let edges_ok = self.enumerate_edges().all(|edge| {
+            let (source, target) = (edge.source(), edge.target());
+            let pair = (source.min(target), source.max(target));
+            let spiders = !self.is_boundary(source) && !self.is_boundary(target);
+            source != target
+                && seen.insert(pair)
+                && (!spiders || *edge.weight() == EdgeType::Hadamard)
+        });
+
+        let vertices_ok = self.enumerate_vertices().all(|(index, vertex)| {
+            match vertex.vertex_type() {
+                VertexType::Z => true,
+                VertexType::B => {
+                    self.degree(index) == 1
+                        && self.neighbours(index).all(|neighbour| {
+                            self.vertex(neighbour).is_some_and(|n| n.vertex_type() == VertexType::Z)
+                        })
+                }
+                _ => false,
+            }
+        });
+        edges_ok && vertices_ok
+    }
+}
+
+/// Replaces the Hadamard vertex at `index` by a Hadamard edge, if it has exactly two edges.
+fn remove_hadamard_vertex(graph: &mut Graph, index: VertexIndex) {
+    let edges: Vec<(VertexIndex, EdgeType)> = graph.incident_edges(index)
+        .map(|edge| (edge.target(), *edge.weight()))
+        .collect();
+    if let [left, right] = edges[..] {
+        if left.0 != index && right.0 != index {
+            bypass(graph, index, [left, right], EdgeType::Hadamard);
+        }
+    }
+}
+
+/// Rewrites the Y spider at `index` into a Z spider with the same phase.
+///
+/// Each leg is an output or an input of the Y spider as given by [`VertexType::Y`]. An output
+/// leg gains a Hadamard edge into a new `π/2` Z spider, and an input leg a Hadamard edge into a
+/// new `-π/2` Z spider.
+fn expand_y(graph: &mut Graph, index: VertexIndex) {
+    let edges: Vec<(EdgeIndex, VertexIndex, VertexIndex, EdgeType)> = graph.incident_edges(index)
+        .map(|edge| {
+            let (source, target) = graph.edge_endpoints(edge.id()).expect("edge exists");
+            (edge.id(), source, target, *edge.weight())
+        })
+        .collect();
+
+    graph.vertex_mut(index).expect("vertex exists").set_vertex_type(VertexType::Z);
+    for (edge, source, target, edge_type) in edges {
+        graph.remove_edge_at(edge);
+        let source = if source == index {
+            let coords = between(graph, index, target, 1.0 / 3.0);
+            insert_spider(graph, index, EdgeType::Hadamard, Phase::plus(), coords)
+        } else {
+            source
+        };
+        let target = if target == index {
+            let coords = between(graph, index, source, 1.0 / 3.0);
+            insert_spider(graph, index, EdgeType::Hadamard, Phase::minus(), coords)
+        } else {
+            target
+        };
+        graph.add_edge_of_type(source, target, edge_type).expect("endpoints exist");
+    }
+}
+
+/// Inserts phase-free Z spiders between each boundary and a neighbouring boundary.
+fn attach_boundaries(graph: &mut Graph) {
+    let boundaries: Vec<VertexIndex> = graph.input_indices()
+        .chain(graph.output_indices())
+        .copied()
+        .collect();
+
+    for boundary in boundaries {
+        let Some((edge, neighbour, edge_type)) = graph.incident_edges(boundary)
+            .next()
+            .map(|edge| (edge.id(), edge.target(), *edge.weight()))
+        else { continue };
+        if !graph.is_boundary(neighbour) {
+            continue;
+        }
+
+        // An even number of Hadamard edges between the spiders leaves a plain wire
+        let length = if edge_type == EdgeType::Simple { 3 } else { 2 };
+        graph.remove_edge_at(edge);
+        let mut previous = boundary;
+        for step in 0..length {
+            let coords = between(graph, boundary, neighbour, (step + 1) as f64 / (length + 1) as f64);
+            let link = if step == 0 { EdgeType::Simple } else { EdgeType::Hadamard };
+            previous = insert_spider(graph, previous, link, Phase::zero(), coords);
+        }
+        graph.add_edge(previous, neighbour).expect("endpoints exist");
+    }
+}
+
+/// Adds a Z spider with `phase` at `coords`, joined to `neighbour` by an edge of `edge_type`.
+fn insert_spider(
+    graph: &mut Graph,
+    neighbour: VertexIndex,
+    edge_type: EdgeType,
+    phase: Phase,
+    coords: Option<Coords>,
+) -> VertexIndex {
+    let mut vertex: Vertex = VertexBuilder::z().phase(phase).build();
+    if let Some(coords) = coords {
+        vertex.set_coords(coords);
+    }
+    let index = graph.add_vertex(vertex);
+    graph.add_edge_of_type(neighbour, index, edge_type).expect("neighbour exists");
+    index
+}
+
+/// Returns the point a fraction `t` of the way from `a` to `b`, if both are positioned.
+fn between(graph: &Graph, a: VertexIndex, b: VertexIndex, t: f64) -> Option<Coords> {
+    let a = graph.vertex(a)?.coords()?;
+    let b = graph.vertex(b)?.coords()?;
+    Some(Coords { x: a.x + (b.x - a.x) * t, y: a.y + (b.y - a.y) * t })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, Gadget, GraphBuilder, Pauli};
+    use crate::graph::phase::Phase;
+    use crate::graph::{EdgeType, Graph, VertexBuilder, VertexType};
+    use crate::rewrite::{ColourChange, GraphLike, RewriteError};
+
+    #[test]
+    fn change_colour_toggles_edges() {
+        let mut graph = GraphBuilder::cx(0, 1);
+        let x = graph.enumerate_vertices()
+            .find(|(_, vertex)| vertex.vertex_type() == VertexType::X)
+            .map(|(index, _)| index)
+            .unwrap();
+
+        graph.change_colour(x).unwrap();
+        assert_eq!(graph.vertex(x).unwrap().vertex_type(), VertexType::Z);
+        assert_eq!(graph.edges().filter(|&&edge| edge == EdgeType::Hadamard).count(), 3);
+
+        graph.change_colour(x).unwrap();
+        assert!(graph.edges().all(|&edge| edge == EdgeType::Simple));
+    }
+
+    #[test]
+    fn change_colour_keeps_self_loops() {
+        let mut graph = Graph::new(0);
+        let z = graph.add_vertex(VertexBuilder::z().build());
+        graph.add_edge(z, z).unwrap();
+
+        graph.change_colour(z).unwrap();
+        assert_eq!(graph.edge_type(z, z), Some(EdgeType::Simple));
+
+        let boundary = graph.add_vertex(VertexBuilder::b().build());
+        assert!(matches!(graph.change_colour(boundary), Err(RewriteError::NotSpider(_))));
+    }
+
+    #[test]
+    fn cx_to_graph_like() {
+        let mut graph = GraphBuilder::cx(0, 1);
+        assert!(!graph.is_graph_like());
+
+        graph.to_graph_like();
+        assert!(graph.is_graph_like());
+        assert_eq!(graph.num_vertices(), 6);
+        assert_eq!(graph.num_edges(), 5);
+    }
+
+    #[test]
+    fn to_graph_like_removes_parallel_edges_and_self_loops() {
+        let mut graph = GraphBuilder::cx(0, 1).compose(&GraphBuilder::cx(0, 1)).unwrap();
+        graph.to_graph_like();
+        assert!(graph.is_graph_like());
+        assert_eq!(graph.num_edges(), 4);
+
+        let mut graph = Graph::new(0);
+        let z = graph.add_vertex(VertexBuilder::z().build());
+        graph.add_edge_of_type(z, z, EdgeType::Hadamard).unwrap();
+        graph.to_graph_like();
+        assert_eq!(graph.num_edges(), 0);
+        assert_eq!(graph.vertex(z).unwrap().phase(), Phase::one());
+    }
+
+    #[test]
+    fn to_graph_like_attaches_bare_wires() {
+        let mut graph = GraphBuilder::pauli_z(1);
+        graph.to_graph_like();
+        assert!(graph.is_graph_like());
+        assert_eq!(graph.num_vertices(), 8);
+    }
+
+    #[test]
+    fn to_graph_like_expands_y_spiders() {
+        let mut graph = GraphBuilder::y_plus(0);
+        graph.to_graph_like();
+        assert!(graph.is_graph_like());
+        assert!(graph.vertices().all(|vertex| vertex.vertex_type() != VertexType::Y));
+
+        let mut graph = GraphBuilder::gadget("zxy", Phase::plus()).unwrap();
+        graph.to_graph_like();
+        assert!(graph.is_graph_like());
+    }
+}
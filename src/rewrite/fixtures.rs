@@ -0,0 +1,15 @@
+use crate::graph::{Graph, VertexBuilder, VertexIndex};
+use std::array;
+
+/// Builds a graph with `N` phase-free Z spiders, each attached to the output of its own qubit.
+pub(super) fn output_spiders<const N: usize>() -> (Graph, [VertexIndex; N]) {
+    let mut graph = Graph::new(N);
+    let spiders = array::from_fn(|qubit| {
+        let spider = graph.add_vertex(VertexBuilder::z().build());
+        let output = graph.add_vertex(VertexBuilder::b().build());
+        graph.add_edge(spider, output).unwrap();
+        graph.set_output(qubit, output).unwrap();
+        spider
+    });
+    (graph, spiders)
+}
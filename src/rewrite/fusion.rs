@@ -0,0 +1,171 @@
+use crate::graph::{EdgeType, Graph, VertexIndex, VertexType};
+use crate::rewrite::{RewriteError, SpiderFusion};
+use petgraph::prelude::EdgeRef;
+
+impl SpiderFusion for Graph {
+    /// Rewrite: fuses spider `w` into spider `v`
+    ///
+    /// `v` and `w` must be distinct spiders of the same colour joined by a `Simple` edge. One such
+    /// edge is consumed, the phase of `w` is added to `v` and every other edge of `w` is moved onto
+    /// `v`, so edges left between them become self-loops.
+    fn fuse(&mut self, v: VertexIndex, w: VertexIndex) -> Result<(), RewriteError> {
+        let vertex_type = spider_type(self, v)?;
+        if spider_type(self, w)? != vertex_type {
+            return Err(RewriteError::ColourMismatch(v.index(), w.index()));
+        }
+
+        let edge = self.incident_edges(v)
+            .find(|edge| v != w && edge.target() == w && *edge.weight() == EdgeType::Simple)
+            .map(|edge| edge.id())
+            .ok_or(RewriteError::NotConnected(v.index(), w.index()))?;
+        self.remove_edge_at(edge);
+
+        // Keep the stored direction of each moved edge, see `VertexType::Y`
+        let edges: Vec<(VertexIndex, VertexIndex, EdgeType)> = self.incident_edges(w)
+            .map(|edge| {
+                let (source, target) = self.edge_endpoints(edge.id()).expect("edge exists");
+                (source, target, *edge.weight())
+            })
+            .collect();
+        let fused = self.remove_vertex(w).expect("vertex was checked above");
+        for (source, target, edge_type) in edges {
+            let source = if source == w { v } else { source };
+            let target = if target == w { v } else { target };
+            self.add_edge_of_type(source, target, edge_type).expect("endpoints exist");
+        }
+
+        let vertex = self.vertex_mut(v).expect("vertex was checked above");
+        vertex.set_phase(vertex.phase() + fused.phase());
+        Ok(())
+    }
+
+    /// Rewrite: fuses adjacent same-colour spiders until none remain
+    fn fuse_all(&mut self) -> usize {
+        let mut count = 0;
+        while let Some((v, w)) = find_fusion(self) {
+            self.fuse(v, w).expect("match satisfies the fusion preconditions");
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Returns the type of the spider at `index`, failing if it is missing or not a Z or X spider.
+pub(super) fn spider_type(graph: &Graph, index: VertexIndex) -> Result<VertexType, RewriteError> {
+    let vertex = graph.vertex(index).ok_or(RewriteError::MissingVertex(index.index()))?;
+    if !vertex.is_spider() {
+        return Err(RewriteError::NotSpider(index.index()));
+    }
+    Ok(vertex.vertex_type())
+}
+
+/// Returns the endpoints of a `Simple` edge between two distinct spiders of the same colour.
+pub(super) fn find_fusion(graph: &Graph) -> Option<(VertexIndex, VertexIndex)> {
+    graph.enumerate_edges()
+        .filter(|edge| *edge.weight() == EdgeType::Simple && edge.source() != edge.target())
+        .map(|edge| (edge.source(), edge.target()))
+        .find(|&(v, w)| {
+            matches!((spider_type(graph, v), spider_type(graph, w)), (Ok(a), Ok(b)) if a == b)
+        })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, GraphBuilder, Pauli};
+    use crate::graph::phase::Phase;
+    use crate::graph::{Graph, VertexBuilder, VertexIndex, VertexType};
+    use crate::rewrite::{RewriteError, SpiderFusion};
+    use petgraph::prelude::EdgeRef;
+
+    fn first(graph: &Graph, vertex_type: VertexType) -> VertexIndex {
+        graph.enumerate_vertices()
+            .find(|(_, vertex)| vertex.vertex_type() == vertex_type)
+            .map(|(index, _)| index)
+            .unwrap()
+    }
+
+    fn spiders(graph: &Graph) -> Vec<(VertexType, Phase)> {
+        graph.vertices()
+            .filter(|vertex| vertex.is_spider())
+            .map(|vertex| (vertex.vertex_type(), vertex.phase()))
+            .collect()
+    }
+
+    #[test]
+    fn fuse_adds_phases() {
+        let mut graph = GraphBuilder::z_plus(0).compose(&GraphBuilder::z_plus(0)).unwrap();
+        let (v, w) = graph.enumerate_edges()
+            .map(|edge| (edge.source(), edge.target()))
+            .find(|&(v, w)| !graph.is_boundary(v) && !graph.is_boundary(w))
+            .unwrap();
+
+        graph.fuse(v, w).unwrap();
+        assert_eq!(spiders(&graph), [(VertexType::Z, Phase::one())]);
+        assert_eq!(graph.num_vertices(), 3);
+        assert_eq!(graph.num_edges(), 2);
+        assert!(graph.vertex(w).is_none());
+    }
+
+    #[test]
+    fn fuse_adds_symbolic_phases() {
+        let mut graph = Graph::new(1);
+        let v = graph.add_vertex_along_wire(0, VertexBuilder::z().phase(Phase::parameter("\\theta")).build()).unwrap();
+        let w = graph.add_vertex_along_wire(0, VertexBuilder::z().phase(Phase::new(0.25)).build()).unwrap();
+
+        graph.fuse(v, w).unwrap();
+        let phase = Phase::parameter("\\theta") + Phase::new(0.25);
+        assert_eq!(spiders(&graph), [(VertexType::Z, phase)]);
+
+        graph.map_phases(|phase| phase.substitute("\\theta", &Phase::new(0.25)));
+        assert_eq!(spiders(&graph), [(VertexType::Z, Phase::plus())]);
+    }
+
+    #[test]
+    fn fuse_all_merges_wire() {
+        let mut graph = GraphBuilder::pauli_x(0)
+            .compose(&GraphBuilder::x_plus(0)).unwrap()
+            .compose(&GraphBuilder::x_minus(0)).unwrap();
+
+        assert_eq!(graph.fuse_all(), 2);
+        assert_eq!(spiders(&graph), [(VertexType::X, Phase::one())]);
+    }
+
+    #[test]
+    fn fuse_all_leaves_parallel_edges() {
+        let mut graph = GraphBuilder::cx(0, 1).compose(&GraphBuilder::cx(0, 1)).unwrap();
+
+        assert_eq!(graph.fuse_all(), 2);
+        assert_eq!(graph.num_vertices(), 6);
+        assert_eq!(graph.num_edges(), 6);
+    }
+
+    #[test]
+    fn fuse_moves_edges_between_spiders_into_self_loops() {
+        let mut graph = Graph::new(0);
+        let v = graph.add_vertex(VertexBuilder::z().build());
+        let w = graph.add_vertex(VertexBuilder::z().build());
+        graph.add_edge(v, w).unwrap();
+        graph.add_edge(v, w).unwrap();
+
+        graph.fuse(v, w).unwrap();
+        assert_eq!(graph.num_vertices(), 1);
+        assert_eq!(graph.num_edges(), 1);
+        assert!(graph.edge_index(v, v).is_some());
+    }
+
+    #[test]
+    fn fuse_checks_preconditions() {
+        let mut graph = GraphBuilder::cx(0, 1);
+        let z = first(&graph, VertexType::Z);
+        let x = first(&graph, VertexType::X);
+        let input = *graph.input_index(0).unwrap();
+
+        assert!(matches!(graph.fuse(z, input), Err(RewriteError::NotSpider(_))));
+        assert!(matches!(graph.fuse(z, z), Err(RewriteError::NotConnected(_, _))));
+        assert!(matches!(graph.fuse(z, x), Err(RewriteError::ColourMismatch(_, _))));
+
+        graph.remove_vertex(x);
+        assert!(matches!(graph.fuse(z, x), Err(RewriteError::MissingVertex(_))));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::graph::phase::Phase;
+use crate::graph::{EdgeType, Graph, VertexIndex, VertexType};
+use crate::rewrite::{GadgetFusion, PhaseGadget, RewriteError};
+use petgraph::prelude::EdgeRef;
+
+impl GadgetFusion for Graph {
+    /// Returns the phase gadget with hub `hub`, if there is one
+    ///
+    /// `hub` must be a Z spider with phase `0` or `π` joined only to Z spiders, each by a single
+    /// `Hadamard` edge. Exactly one of them must be an arity-1 leaf, and at least one other must
+    /// be a leg.
+    fn phase_gadget(&self, hub: VertexIndex) -> Option<PhaseGadget> {
+        let vertex = self.vertex(hub)?;
+        let phase = vertex.phase();
+        if vertex.vertex_type() != VertexType::Z || !phase.is_constant() || !phase.is_pauli() {
+            return None;
+        }
+
+        let mut leaf = None;
+        let mut legs = Vec::with_capacity(self.degree(hub));
+        for edge in self.incident_edges(hub) {
+            let neighbour = edge.target();
+            let spider = self.vertex(neighbour).is_some_and(|n| n.vertex_type() == VertexType::Z);
+            if !spider || neighbour == hub || *edge.weight() != EdgeType::Hadamard || legs.contains(&neighbour) {
+                return None;
+            }
+            if self.degree(neighbour) == 1 {
+                if leaf.replace(neighbour).is_some() {
+                    return None;
+                }
+            } else {
+                legs.push(neighbour);
+            }
+        }
+
+        if legs.is_empty() {
+            return None;
+        }
+        legs.sort();
+        leaf.map(|leaf| PhaseGadget { hub, leaf, legs })
+    }
+
+    /// Returns every phase gadget in the diagram
+    ///
+    /// Runs in **O(n + m)** time, where *n* is the number of vertices and *m* the number of edges.
+    fn find_gadgets(&self) -> Vec<PhaseGadget> {
+        self.enumerate_vertices()
+            .filter_map(|(index, _)| self.phase_gadget(index))
+            .collect()
+    }
+
+    /// Rewrite: merges the phase gadget with hub `w` into the gadget with hub `v`
+    ///
+    /// Both gadgets must act on the same legs. A hub with phase `π` is first made phase-free by
+    /// negating the phase `α` of its leaf and multiplying the scalar by `e^(iα)`, after which the
+    /// leaf of `v` gains the phase of the leaf of `w`, and the hub and leaf of `w` are removed.
+    /// For *k* legs, the scalar is multiplied by `√2^(1-k)`.
+    fn fuse_gadgets(&mut self, v: VertexIndex, w: VertexIndex) -> Result<(), RewriteError> {
+        let first = self.phase_gadget(v).ok_or(RewriteError::NotGadget(v.index()))?;
+        let second = self.phase_gadget(w).ok_or(RewriteError::NotGadget(w.index()))?;
+        if v == w || first.legs != second.legs {
+            return Err(RewriteError::GadgetLegsMismatch(v.index(), w.index()));
+        }
+
+        let phase = leaf_phase(self, &first) + leaf_phase(self, &second);
+        self.scalar_mut().mul_sqrt2_power(1 - first.legs.len() as i32);
+        let hub = self.vertex_mut(v).expect("hub exists");
+        hub.set_phase(Phase::zero());
+        self.vertex_mut(first.leaf).expect("leaf exists").set_phase(phase);
+        self.remove_vertex(second.leaf);
+        self.remove_vertex(w);
+        Ok(())
+    }
+
+    /// Rewrite: merges phase gadgets acting on the same legs until none remain
+    fn fuse_gadgets_all(&mut self) -> usize {
+        let mut count = 0;
+        while let Some((v, w)) = find_gadget_fusion(self) {
+            self.fuse_gadgets(v, w).expect("gadgets act on the same legs");
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Returns the phase of the leaf of `gadget`, negated if its hub has phase `π`, in which case
+/// the scalar is multiplied by `e^(iα)` for the phase `α` of the leaf.
+fn leaf_phase(graph: &mut Graph, gadget: &PhaseGadget) -> Phase {
+    let phase = graph.vertex(gadget.leaf).expect("leaf exists").phase();
+    if graph.vertex(gadget.hub).expect("hub exists").phase() == Phase::one() {
+        graph.scalar_mut().mul_phase(phase.clone());
+        -phase
+    } else {
+        phase
+    }
+}
+
+/// Returns the hubs of a pair of phase gadgets acting on the same legs, if there is one.
+pub(super) fn find_gadget_fusion(graph: &Graph) -> Option<(VertexIndex, VertexIndex)> {
+    let mut gadgets = graph.find_gadgets();
+    gadgets.sort_by(|a, b| a.legs.cmp(&b.legs));
+    gadgets.windows(2)
+        .find(|pair| pair[0].legs == pair[1].legs)
+        .map(|pair| (pair[0].hub, pair[1].hub))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::phase::Phase;
+    use crate::graph::{EdgeType, Graph, VertexBuilder, VertexIndex};
+    use crate::rewrite::fixtures::output_spiders;
+    use crate::rewrite::{GadgetFusion, RewriteError};
+    use crate::tensor::Evaluate;
+
+    /// Adds a phase gadget with `phase` on its leaf and `hub_phase` on its hub, acting on `legs`.
+    fn add_gadget(graph: &mut Graph, legs: &[VertexIndex], hub_phase: Phase, phase: Phase) -> VertexIndex {
+        let hub = graph.add_vertex(VertexBuilder::z().phase(hub_phase).build());
+        let leaf = graph.add_vertex(VertexBuilder::z().phase(phase).build());
+        graph.add_edge_of_type(hub, leaf, EdgeType::Hadamard).unwrap();
+        for &leg in legs {
+            graph.add_edge_of_type(hub, leg, EdgeType::Hadamard).unwrap();
+        }
+        hub
+    }
+
+    #[test]
+    fn finds_phase_gadgets() {
+        let (mut graph, legs) = output_spiders::<3>();
+        let hub = add_gadget(&mut graph, &legs[..2], Phase::zero(), Phase::new(0.25));
+
+        let gadgets = graph.find_gadgets();
+        assert_eq!(gadgets.len(), 1);
+        assert_eq!(gadgets[0].hub, hub);
+        assert_eq!(gadgets[0].legs, legs[..2]);
+        assert_eq!(graph.vertex(gadgets[0].leaf).unwrap().phase(), Phase::new(0.25));
+
+        graph.vertex_mut(hub).unwrap().set_phase(Phase::plus());
+        assert!(graph.find_gadgets().is_empty());
+    }
+
+    #[test]
+    fn fuse_gadgets_sums_phases() {
+        let (mut graph, legs) = output_spiders::<2>();
+        let v = add_gadget(&mut graph, &legs, Phase::zero(), Phase::new(0.25));
+        let w = add_gadget(&mut graph, &legs, Phase::one(), Phase::new(0.75));
+        add_gadget(&mut graph, &legs[..1], Phase::zero(), Phase::new(0.25));
+
+        let expected = graph.to_matrix();
+        assert_eq!(graph.fuse_gadgets_all(), 1);
+        assert!(graph.to_matrix().approx_eq(&expected, 1e-9));
+        assert!(graph.vertex(w).is_none());
+        let gadget = graph.phase_gadget(v).unwrap();
+        assert_eq!(graph.vertex(v).unwrap().phase(), Phase::zero());
+        assert_eq!(graph.vertex(gadget.leaf).unwrap().phase(), Phase::new(1.5));
+        assert_eq!(graph.find_gadgets().len(), 2);
+    }
+
+    #[test]
+    fn fuse_gadgets_rejects_different_legs() {
+        let (mut graph, legs) = output_spiders::<2>();
+        let v = add_gadget(&mut graph, &legs, Phase::zero(), Phase::plus());
+        let w = add_gadget(&mut graph, &legs[..1], Phase::zero(), Phase::plus());
+        assert!(matches!(graph.fuse_gadgets(v, w), Err(RewriteError::GadgetLegsMismatch(_, _))));
+        assert!(matches!(graph.fuse_gadgets(v, legs[0]), Err(RewriteError::NotGadget(_))));
+        assert_eq!(graph.fuse_gadgets_all(), 0);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::graph::phase::Phase;
+use crate::graph::{EdgeType, Graph, VertexIndex};
+use crate::rewrite::fusion::spider_type;
+use crate::rewrite::{Hopf, Normalisation, RewriteError};
+use petgraph::prelude::{EdgeIndex, EdgeRef};
+
+impl Hopf for Graph {
+    /// Rewrite: removes two parallel edges between spiders `v` and `w`
+    ///
+    /// Spiders of opposite colour cancel a pair of `Simple` edges and spiders of the same colour
+    /// cancel a pair of `Hadamard` edges, multiplying the scalar by 1/2.
+    fn hopf(&mut self, v: VertexIndex, w: VertexIndex) -> Result<(), RewriteError> {
+        match cancelling_edges(self, v, w)?[..] {
+            [first, second, ..] => {
+                self.remove_edge_at(first);
+                self.remove_edge_at(second);
+                self.scalar_mut().mul_sqrt2_power(-2);
+                Ok(())
+            }
+            _ => Err(RewriteError::NoParallelEdges(v.index(), w.index())),
+        }
+    }
+
+    /// Rewrite: removes a self-loop from spider `v`
+    ///
+    /// A `Simple` loop is removed first and leaves the diagram unchanged. A `Hadamard` loop adds
+    /// `π` to the phase of `v` and multiplies the scalar by 1/√2.
+    fn remove_self_loop(&mut self, v: VertexIndex) -> Result<(), RewriteError> {
+        spider_type(self, v)?;
+        let (edge, edge_type) = self.incident_edges(v)
+            .filter(|edge| edge.target() == v)
+            .map(|edge| (edge.id(), *edge.weight()))
+            .min_by_key(|&(_, edge_type)| edge_type == EdgeType::Hadamard)
+            .ok_or(RewriteError::NoSelfLoop(v.index()))?;
+
+        self.remove_edge_at(edge);
+        if edge_type == EdgeType::Simple {
+            return Ok(());
+        }
+        let vertex = self.vertex_mut(v).expect("vertex was checked above");
+        vertex.set_phase(vertex.phase() + Phase::one());
+        self.scalar_mut().mul_sqrt2_power(-1);
+        Ok(())
+    }
+
+    /// Rewrite: removes cancelling parallel edges and self-loops on spiders until none remain
+    fn normalise_edges(&mut self) -> Normalisation {
+        let mut normalisation = Normalisation::default();
+        while let Some(v) = find_self_loop(self) {
+            self.remove_self_loop(v).expect("match has a self-loop");
+            normalisation.self_loops += 1;
+        }
+        while let Some((v, w)) = find_hopf(self) {
+            self.hopf(v, w).expect("match satisfies the Hopf law");
+            normalisation.hopf += 1;
+        }
+        normalisation
+    }
+}
+
+/// Returns the edges between spiders `v` and `w` that cancel in pairs under the Hopf law.
+fn cancelling_edges(graph: &Graph, v: VertexIndex, w: VertexIndex) -> Result<Vec<EdgeIndex>, RewriteError> {
+    let cancelling = if spider_type(graph, v)? == spider_type(graph, w)? {
+        EdgeType::Hadamard
+    } else {
+        EdgeType::Simple
+    };
+
+    Ok(graph.incident_edges(v)
+        .filter(|edge| v != w && edge.target() == w && *edge.weight() == cancelling)
+        .map(|edge| edge.id())
+        .collect())
+}
+
+/// Returns a spider with a self-loop.
+pub(super) fn find_self_loop(graph: &Graph) -> Option<VertexIndex> {
+    graph.enumerate_edges()
+        .filter(|edge| edge.source() == edge.target())
+        .map(|edge| edge.source())
+        .find(|&v| spider_type(graph, v).is_ok())
+}
+
+/// Returns a pair of spiders joined by at least two cancelling edges.
+pub(super) fn find_hopf(graph: &Graph) -> Option<(VertexIndex, VertexIndex)> {
+    graph.enumerate_edges()
+        .map(|edge| (edge.source(), edge.target()))
+        .find(|&(v, w)| cancelling_edges(graph, v, w).is_ok_and(|edges| edges.len() >= 2))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, GraphBuilder};
+    use crate::graph::phase::Phase;
+    use crate::graph::scalar::Scalar;
+    use crate::graph::{EdgeType, Graph, VertexBuilder};
+    use crate::rewrite::{Hopf, Normalisation, RewriteError, SpiderFusion};
+
+    #[test]
+    fn hopf_cancels_simple_edges_between_opposite_colours() {
+        let mut graph = GraphBuilder::cx(0, 1).compose(&GraphBuilder::cx(0, 1)).unwrap();
+        graph.fuse_all();
+
+        let normalisation = graph.normalise_edges();
+        assert_eq!(normalisation, Normalisation { hopf: 1, self_loops: 0 });
+        assert_eq!(*graph.scalar(), Scalar::new(-2, Phase::zero()));
+        assert_eq!(graph.num_edges(), 4);
+    }
+
+    #[test]
+    fn hopf_cancels_hadamard_edges_between_same_colours() {
+        let mut graph = Graph::new(0);
+        let v = graph.add_vertex(VertexBuilder::x().build());
+        let w = graph.add_vertex(VertexBuilder::x().build());
+        graph.add_edge_of_type(v, w, EdgeType::Hadamard).unwrap();
+        graph.add_edge_of_type(v, w, EdgeType::Hadamard).unwrap();
+        graph.add_edge_of_type(v, w, EdgeType::Hadamard).unwrap();
+        graph.add_edge(v, w).unwrap();
+
+        graph.hopf(v, w).unwrap();
+        assert_eq!(graph.scalar().sqrt2_power(), -2);
+        assert!(matches!(graph.hopf(v, w), Err(RewriteError::NoParallelEdges(_, _))));
+        assert_eq!(graph.num_edges(), 2);
+    }
+
+    #[test]
+    fn hadamard_self_loop_adds_pi() {
+        let mut graph = Graph::new(0);
+        let v = graph.add_vertex(VertexBuilder::x_plus().build());
+        graph.add_edge_of_type(v, v, EdgeType::Hadamard).unwrap();
+        graph.add_edge(v, v).unwrap();
+
+        graph.remove_self_loop(v).unwrap();
+        assert_eq!(graph.vertex(v).unwrap().phase(), Phase::plus());
+        assert_eq!(graph.scalar().sqrt2_power(), 0);
+        graph.remove_self_loop(v).unwrap();
+        assert_eq!(graph.vertex(v).unwrap().phase(), Phase::minus());
+        assert_eq!(graph.scalar().sqrt2_power(), -1);
+        assert!(matches!(graph.remove_self_loop(v), Err(RewriteError::NoSelfLoop(_))));
+    }
+
+    #[test]
+    fn normalise_edges_leaves_single_edges() {
+        let mut graph = GraphBuilder::cz(0, 1);
+        assert_eq!(graph.normalise_edges(), Normalisation::default());
+        assert_eq!(graph.num_edges(), 5);
+    }
+}
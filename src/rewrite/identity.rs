@@ -0,0 +1,126 @@
+use crate::graph::phase::Phase;
+use crate::graph::{EdgeType, Graph, VertexIndex};
+use crate::rewrite::{IdentityRemoval, RewriteError};
+use petgraph::prelude::EdgeRef;
+
+impl IdentityRemoval for Graph {
+    /// Rewrite: removes the identity spider `v`
+    ///
+    /// `v` must be a Z or X spider with zero phase and exactly two edges, neither of which is a
+    /// self-loop. Its neighbours are joined by a single edge whose type is the composition of the
+    /// two removed edges. Boundaries are ordinary neighbours, so inputs and outputs are kept.
+    fn remove_identity(&mut self, v: VertexIndex) -> Result<(), RewriteError> {
+        let edges = identity_edges(self, v)?;
+        bypass(self, v, edges, EdgeType::Simple);
+        Ok(())
+    }
+
+    /// Rewrite: removes identity spiders until none remain
+    fn remove_identities(&mut self) -> usize {
+        let mut count = 0;
+        while let Some(v) = find_identity(self) {
+            self.remove_identity(v).expect("match satisfies the identity preconditions");
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Removes the arity-2 vertex `v`, joining its two neighbours by a single edge.
+///
+/// The new edge composes both removed edges with `through`, the edge type `v` acted as, and
+/// keeps the stored direction through `v`, see [`VertexType::Y`](crate::graph::VertexType::Y).
+pub(super) fn bypass(graph: &mut Graph, v: VertexIndex, edges: [(VertexIndex, EdgeType); 2], through: EdgeType) {
+    let [(left, left_type), (right, right_type)] = edges;
+    let into_v = graph.incident_edges(v)
+        .find(|edge| edge.target() == left)
+        .and_then(|edge| graph.edge_endpoints(edge.id()))
+        .is_some_and(|(_, target)| target == v);
+    let (source, target) = if into_v { (left, right) } else { (right, left) };
+
+    graph.remove_vertex(v);
+    graph.add_edge_of_type(source, target, left_type.compose(through).compose(right_type)).expect("neighbours exist");
+}
+
+/// Returns the two edges of the identity spider at `index`, as neighbour and edge type.
+fn identity_edges(graph: &Graph, index: VertexIndex) -> Result<[(VertexIndex, EdgeType); 2], RewriteError> {
+    let vertex = graph.vertex(index).ok_or(RewriteError::MissingVertex(index.index()))?;
+    if !vertex.is_spider() {
+        return Err(RewriteError::NotSpider(index.index()));
+    }
+    if vertex.phase() != Phase::zero() {
+        return Err(RewriteError::NotIdentity(index.index()));
+    }
+
+    let edges: Vec<(VertexIndex, EdgeType)> = graph.incident_edges(index)
+        .map(|edge| (edge.target(), *edge.weight()))
+        .collect();
+    match edges[..] {
+        [left, right] if left.0 != index && right.0 != index => Ok([left, right]),
+        _ => Err(RewriteError::NotIdentity(index.index())),
+    }
+}
+
+/// Returns the index of some identity spider in the graph.
+pub(super) fn find_identity(graph: &Graph) -> Option<VertexIndex> {
+    graph.enumerate_vertices()
+        .map(|(index, _)| index)
+        .find(|&index| identity_edges(graph, index).is_ok())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, GraphBuilder};
+    use crate::graph::{EdgeType, Graph, VertexBuilder};
+    use crate::rewrite::{IdentityRemoval, RewriteError, SpiderFusion};
+
+    #[test]
+    fn removes_identity_between_boundaries() {
+        let mut graph = GraphBuilder::z_plus(0).compose(&GraphBuilder::z_minus(0)).unwrap();
+        graph.fuse_all();
+
+        assert_eq!(graph.remove_identities(), 1);
+        assert_eq!(graph.num_vertices(), 2);
+        assert_eq!(graph.num_edges(), 1);
+
+        let input = *graph.input_index(0).unwrap();
+        let output = *graph.output_index(0).unwrap();
+        assert_eq!(graph.edge_type(input, output), Some(EdgeType::Simple));
+    }
+
+    #[test]
+    fn composes_edge_types() {
+        let mut graph = Graph::new(0);
+        let a = graph.add_vertex(VertexBuilder::z_plus().build());
+        let v = graph.add_vertex(VertexBuilder::x().build());
+        let w = graph.add_vertex(VertexBuilder::z().build());
+        let b = graph.add_vertex(VertexBuilder::z_plus().build());
+        graph.add_edge_of_type(a, v, EdgeType::Hadamard).unwrap();
+        graph.add_edge_of_type(v, w, EdgeType::Hadamard).unwrap();
+        graph.add_edge(w, b).unwrap();
+
+        graph.remove_identity(v).unwrap();
+        assert_eq!(graph.edge_type(a, w), Some(EdgeType::Simple));
+
+        graph.remove_edge(a, w);
+        graph.add_edge_of_type(a, w, EdgeType::Hadamard).unwrap();
+        graph.remove_identity(w).unwrap();
+        assert_eq!(graph.edge_type(a, b), Some(EdgeType::Hadamard));
+        assert_eq!(graph.num_edges(), 1);
+    }
+
+    #[test]
+    fn keeps_spiders_with_phase_or_other_arity() {
+        let mut graph = GraphBuilder::cx(0, 1);
+        assert_eq!(graph.remove_identities(), 0);
+        assert_eq!(graph.num_vertices(), 6);
+
+        let mut graph = GraphBuilder::z_plus(0);
+        let z = graph.enumerate_vertices()
+            .find(|(_, vertex)| vertex.is_spider())
+            .map(|(index, _)| index)
+            .unwrap();
+        assert!(matches!(graph.remove_identity(z), Err(RewriteError::NotIdentity(_))));
+    }
+}
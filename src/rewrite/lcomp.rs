@@ -0,0 +1,149 @@
+use crate::graph::phase::Phase;
+use crate::graph::{EdgeType, Graph, VertexIndex, VertexType};
+use crate::rewrite::{LocalComplementation, RewriteError};
+use petgraph::prelude::EdgeRef;
+
+impl LocalComplementation for Graph {
+    /// Returns every interior ±π/2 Z spider that local complementation can remove
+    fn find_lcomp_candidates(&self) -> Vec<VertexIndex> {
+        self.enumerate_vertices()
+            .map(|(index, _)| index)
+            .filter(|&index| lcomp_neighbours(self, index).is_ok())
+            .collect()
+    }
+
+    /// Rewrite: removes the interior ±π/2 Z spider `v` by local complementation
+    ///
+    /// `v` must only be joined to other Z spiders, each by a single `Hadamard` edge. The
+    /// `Hadamard` edges among its neighbours are complemented and the phase of `v` is subtracted
+    /// from each neighbour, after which `v` is removed. For *n* neighbours, the scalar is
+    /// multiplied by `√2^((n-1)(n-2)/2)` and by `e^(±iπ/4)` for a `±π/2` spider, before the
+    /// edges that already existed cancel.
+    fn lcomp(&mut self, v: VertexIndex) -> Result<(), RewriteError> {
+        let neighbours = lcomp_neighbours(self, v)?;
+        let phase = self.vertex(v).expect("vertex was checked above").phase();
+        self.remove_vertex(v);
+
+        let n = neighbours.len() as i32;
+        let scalar = self.scalar_mut();
+        scalar.mul_sqrt2_power((n - 1) * (n - 2) / 2);
+        scalar.mul_phase(if phase == Phase::plus() { Phase::new(0.25) } else { Phase::new(-0.25) });
+
+        for (i, &a) in neighbours.iter().enumerate() {
+            for &b in &neighbours[i + 1..] {
+                toggle_hadamard_edge(self, a, b);
+            }
+            let neighbour = self.vertex_mut(a).expect("neighbour exists");
+            neighbour.set_phase(neighbour.phase() + -phase.clone());
+        }
+        Ok(())
+    }
+
+    /// Rewrite: applies local complementation until no candidates remain
+    fn lcomp_all(&mut self) -> usize {
+        let mut count = 0;
+        while let Some(&v) = self.find_lcomp_candidates().first() {
+            self.lcomp(v).expect("candidate satisfies the local complementation preconditions");
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Returns the neighbours of `v`, failing unless it is an interior ±π/2 Z spider.
+fn lcomp_neighbours(graph: &Graph, v: VertexIndex) -> Result<Vec<VertexIndex>, RewriteError> {
+    let vertex = graph.vertex(v).ok_or(RewriteError::MissingVertex(v.index()))?;
+    let phase = vertex.phase();
+    if vertex.vertex_type() != VertexType::Z || !phase.is_constant() || !phase.is_proper_clifford() {
+        return Err(RewriteError::NotLcompCandidate(v.index()));
+    }
+
+    let mut neighbours = Vec::with_capacity(graph.degree(v));
+    for edge in graph.incident_edges(v) {
+        let neighbour = edge.target();
+        let interior = graph.vertex(neighbour).is_some_and(|n| n.vertex_type() == VertexType::Z);
+        if !interior || neighbour == v || *edge.weight() != EdgeType::Hadamard || neighbours.contains(&neighbour) {
+            return Err(RewriteError::NotLcompCandidate(v.index()));
+        }
+        neighbours.push(neighbour);
+    }
+    Ok(neighbours)
+}
+
+/// Adds a `Hadamard` edge between the Z spiders `a` and `b`.
+///
+/// An existing `Hadamard` edge between them cancels with the new one under the Hopf law, so it
+/// is removed instead and the scalar is multiplied by 1/2.
+pub(super) fn toggle_hadamard_edge(graph: &mut Graph, a: VertexIndex, b: VertexIndex) {
+    let existing = graph.incident_edges(a)
+        .find(|edge| edge.target() == b && *edge.weight() == EdgeType::Hadamard)
+        .map(|edge| edge.id());
+
+    match existing {
+        Some(edge) => {
+            graph.remove_edge_at(edge);
+            graph.scalar_mut().mul_sqrt2_power(-2);
+        }
+        None => { graph.add_edge_of_type(a, b, EdgeType::Hadamard).expect("neighbours exist"); }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, GraphBuilder};
+    use crate::graph::phase::Phase;
+    use crate::graph::{EdgeType, Graph, VertexBuilder, VertexIndex};
+    use crate::rewrite::fixtures::output_spiders;
+    use crate::rewrite::{GraphLike, LocalComplementation, RewriteError};
+    use crate::tensor::Evaluate;
+
+    /// Builds a ±π/2 spider joined to three spiders, each attached to an output.
+    fn star(phase: Phase) -> (Graph, VertexIndex, [VertexIndex; 3]) {
+        let (mut graph, leaves) = output_spiders();
+        let centre = graph.add_vertex(VertexBuilder::z().phase(phase).build());
+        for leaf in leaves {
+            graph.add_edge_of_type(centre, leaf, EdgeType::Hadamard).unwrap();
+        }
+        (graph, centre, leaves)
+    }
+
+    #[test]
+    fn lcomp_complements_neighbourhood() {
+        let (mut graph, centre, [a, b, c]) = star(Phase::plus());
+        graph.add_edge_of_type(a, b, EdgeType::Hadamard).unwrap();
+        assert_eq!(graph.find_lcomp_candidates(), [centre]);
+
+        let expected = graph.to_matrix();
+        graph.lcomp(centre).unwrap();
+        assert!(graph.to_matrix().approx_eq(&expected, 1e-9));
+        assert!(graph.vertex(centre).is_none());
+        assert_eq!(graph.edge_type(a, b), None);
+        assert_eq!(graph.edge_type(a, c), Some(EdgeType::Hadamard));
+        assert_eq!(graph.edge_type(b, c), Some(EdgeType::Hadamard));
+        for leaf in [a, b, c] {
+            assert_eq!(graph.vertex(leaf).unwrap().phase(), Phase::minus());
+        }
+    }
+
+    #[test]
+    fn lcomp_subtracts_negative_phase() {
+        let (mut graph, centre, [a, _, _]) = star(Phase::minus());
+        let expected = graph.to_matrix();
+        assert_eq!(graph.lcomp_all(), 1);
+        assert!(graph.to_matrix().approx_eq(&expected, 1e-9));
+        assert!(graph.vertex(centre).is_none());
+        assert_eq!(graph.vertex(a).unwrap().phase(), Phase::plus());
+    }
+
+    #[test]
+    fn lcomp_rejects_boundary_spiders() {
+        let mut graph = GraphBuilder::z_plus(0);
+        graph.to_graph_like();
+        assert!(graph.find_lcomp_candidates().is_empty());
+
+        let (mut graph, _, [a, _, _]) = star(Phase::one());
+        assert!(graph.find_lcomp_candidates().is_empty());
+        assert!(matches!(graph.lcomp(a), Err(RewriteError::NotLcompCandidate(_))));
+    }
+}
@@ -0,0 +1,250 @@
+use crate::graph::phase::Phase;
+use crate::graph::scalar::Scalar;
+use crate::graph::{EdgeType, Graph, VertexIndex};
+use thiserror::Error;
+
+mod fusion;
+mod identity;
+mod colour;
+mod hopf;
+mod lcomp;
+mod pivot;
+mod gadget;
+mod simplify;
+mod trace;
+mod rule;
+#[cfg(test)]
+mod fixtures;
+
+pub use rule::{Match, Rule};
+
+#[derive(Debug, Error)]
+pub enum RewriteError {
+    #[error("vertex {0} does not exist")] MissingVertex(usize),
+    #[error("vertex {0} is not a Z or X spider")] NotSpider(usize),
+    #[error("vertices {0} and {1} have different colours")] ColourMismatch(usize, usize),
+    #[error("vertices {0} and {1} are not connected by a simple edge")] NotConnected(usize, usize),
+    #[error("vertex {0} is not a phase-free spider with two neighbours")] NotIdentity(usize),
+    #[error("vertices {0} and {1} share no pair of cancelling edges")] NoParallelEdges(usize, usize),
+    #[error("vertex {0} has no self-loop")] NoSelfLoop(usize),
+    #[error("vertex {0} is not an interior ±π/2 Z spider")] NotLcompCandidate(usize),
+    #[error("vertices {0} and {1} cannot be pivoted")] NotPivotCandidate(usize, usize),
+    #[error("vertex {0} is not the hub of a phase gadget")] NotGadget(usize),
+    #[error("gadgets {0} and {1} act on different vertices")] GadgetLegsMismatch(usize, usize),
+    #[error("{0:?} expects {1} matched vertices")] MatchArity(RewriteRule, usize),
+    #[error("step {0} does not reproduce its recorded change")] ReplayMismatch(usize),
+    #[error("rule boundary {0} must be an input or output with a single edge to an interior vertex")] RuleBoundary(usize),
+    #[error("rule sides have different inputs or outputs")] RuleBoundaryMismatch,
+    #[error("rule left-hand side has no interior vertices")] EmptyRule,
+    #[error("vertex {0} is not an interior vertex of the rule")] NotInterior(usize),
+    #[error("vertex {0} is a Y spider, which rules cannot rewrite")] RuleYSpider(usize),
+    #[error("rule left-hand side has a zero scalar, which cannot be divided out")] RuleZeroScalar,
+    #[error("match no longer embeds the rule in the diagram")] StaleMatch,
+}
+
+/// Summary of an edge normalisation pass
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Normalisation {
+    /// Number of edge pairs removed by the Hopf law
+    pub hopf: usize,
+    /// Number of self-loops removed
+    pub self_loops: usize,
+}
+
+/// Number of times each rule was applied by a simplification strategy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimplifyReport {
+    pub fusion: usize,
+    pub identity: usize,
+    pub hopf: usize,
+    pub self_loops: usize,
+    pub lcomp: usize,
+    pub pivot: usize,
+    pub boundary_pivot: usize,
+    pub gadget_pivot: usize,
+    pub gadget_fusion: usize,
+}
+
+impl SimplifyReport {
+    /// Returns the total number of rule applications
+    pub fn total(&self) -> usize {
+        self.fusion + self.identity + self.hopf + self.self_loops + self.lcomp + self.pivot
+            + self.boundary_pivot + self.gadget_pivot + self.gadget_fusion
+    }
+}
+
+/// Simplification strategy, in increasing order of strength
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    InteriorClifford,
+    Clifford,
+    FullReduce,
+}
+
+/// Rewrite rule applied by a [`RewriteStep`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RewriteRule {
+    GraphLike,
+    Fusion,
+    Identity,
+    Hopf,
+    SelfLoop,
+    LocalComplementation,
+    Pivot,
+    BoundaryPivot,
+    GadgetPivot,
+    GadgetFusion,
+}
+
+/// Single rewrite applied to a diagram
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewriteStep {
+    pub rule: RewriteRule,
+    /// Vertices the rule was applied to, in the order the rule takes them
+    pub vertices: Vec<VertexIndex>,
+    pub delta: GraphDelta,
+}
+
+/// Change made to a diagram by a single rewrite
+///
+/// Edges are given with their endpoints in ascending order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphDelta {
+    pub added_vertices: Vec<VertexIndex>,
+    pub removed_vertices: Vec<VertexIndex>,
+    pub added_edges: Vec<(VertexIndex, VertexIndex, EdgeType)>,
+    pub removed_edges: Vec<(VertexIndex, VertexIndex, EdgeType)>,
+    /// Vertices whose phase changed, with their phases before and after
+    pub phases: Vec<(VertexIndex, Phase, Phase)>,
+    /// Global scalar before and after, if it changed
+    pub scalar: Option<(Scalar, Scalar)>,
+}
+
+/// Diagram together with the rewrites applied to it
+#[derive(Debug, Clone)]
+pub struct Derivation {
+    pub initial: Graph,
+    pub steps: Vec<RewriteStep>,
+}
+
+/// A phase gadget in graph-like form
+///
+/// The hub is a Z spider with phase `0` or `π`, joined by `Hadamard` edges to an arity-1 Z spider
+/// carrying the phase of the gadget, its leaf, and to every leg.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhaseGadget {
+    pub hub: VertexIndex,
+    pub leaf: VertexIndex,
+    /// Vertices the gadget acts on, in ascending order
+    pub legs: Vec<VertexIndex>,
+}
+
+pub trait SpiderFusion {
+    /// Fuses spider `w` into the adjacent same-colour spider `v`
+    fn fuse(&mut self, v: VertexIndex, w: VertexIndex) -> Result<(), RewriteError>;
+
+    /// Fuses adjacent same-colour spiders until none remain, returning the number of fusions
+    fn fuse_all(&mut self) -> usize;
+}
+
+pub trait IdentityRemoval {
+    /// Removes the phase-free arity-2 spider `v`, joining its two neighbours
+    fn remove_identity(&mut self, v: VertexIndex) -> Result<(), RewriteError>;
+
+    /// Removes phase-free arity-2 spiders until none remain, returning the number removed
+    fn remove_identities(&mut self) -> usize;
+}
+
+pub trait ColourChange {
+    /// Toggles spider `v` between Z and X, toggling the type of each of its edges
+    fn change_colour(&mut self, v: VertexIndex) -> Result<(), RewriteError>;
+}
+
+pub trait GraphLike {
+    /// Rewrites the diagram into graph-like form
+    fn to_graph_like(&mut self);
+
+    /// Returns true if the diagram is in graph-like form
+    fn is_graph_like(&self) -> bool;
+}
+
+pub trait Hopf {
+    /// Removes a pair of cancelling parallel edges between spiders `v` and `w`
+    fn hopf(&mut self, v: VertexIndex, w: VertexIndex) -> Result<(), RewriteError>;
+
+    /// Removes a self-loop from spider `v`
+    fn remove_self_loop(&mut self, v: VertexIndex) -> Result<(), RewriteError>;
+
+    /// Removes cancelling parallel edges and self-loops on spiders until none remain
+    fn normalise_edges(&mut self) -> Normalisation;
+}
+
+pub trait LocalComplementation {
+    /// Returns every interior ±π/2 Z spider that local complementation can remove
+    fn find_lcomp_candidates(&self) -> Vec<VertexIndex>;
+
+    /// Removes the interior ±π/2 Z spider `v` by local complementation
+    fn lcomp(&mut self, v: VertexIndex) -> Result<(), RewriteError>;
+
+    /// Applies local complementation until no candidates remain, returning the number applied
+    fn lcomp_all(&mut self) -> usize;
+}
+
+pub trait Pivot {
+    /// Returns every pair of adjacent interior Pauli Z spiders that pivoting can remove
+    fn find_pivot_candidates(&self) -> Vec<(VertexIndex, VertexIndex)>;
+
+    /// Removes the adjacent interior Pauli Z spiders `u` and `v` by pivoting
+    fn pivot(&mut self, u: VertexIndex, v: VertexIndex) -> Result<(), RewriteError>;
+
+    /// Applies pivoting until no candidates remain, returning the number applied
+    fn pivot_all(&mut self) -> usize;
+
+    /// Returns every interior Pauli Z spider paired with an adjacent Pauli Z spider on a boundary
+    fn find_boundary_pivot_candidates(&self) -> Vec<(VertexIndex, VertexIndex)>;
+
+    /// Pivots the interior Pauli Z spider `u` with the Pauli Z spider `v` on a boundary
+    fn pivot_boundary(&mut self, u: VertexIndex, v: VertexIndex) -> Result<(), RewriteError>;
+
+    /// Applies boundary pivoting until no candidates remain, returning the number applied
+    fn pivot_boundary_all(&mut self) -> usize;
+
+    /// Returns every interior Pauli Z spider paired with an adjacent interior non-Pauli Z spider
+    fn find_gadget_pivot_candidates(&self) -> Vec<(VertexIndex, VertexIndex)>;
+
+    /// Pivots the interior Pauli Z spider `u` with the interior non-Pauli Z spider `v`, moving
+    /// the phase of `v` onto a new phase gadget
+    fn pivot_gadget(&mut self, u: VertexIndex, v: VertexIndex) -> Result<(), RewriteError>;
+
+    /// Applies gadget pivoting until no candidates remain, returning the number applied
+    fn pivot_gadget_all(&mut self) -> usize;
+}
+
+pub trait GadgetFusion {
+    /// Returns the phase gadget with hub `hub`, if there is one
+    fn phase_gadget(&self, hub: VertexIndex) -> Option<PhaseGadget>;
+
+    /// Returns every phase gadget in the diagram
+    fn find_gadgets(&self) -> Vec<PhaseGadget>;
+
+    /// Merges the phase gadget with hub `w` into the gadget with hub `v`, which act on the same legs
+    fn fuse_gadgets(&mut self, v: VertexIndex, w: VertexIndex) -> Result<(), RewriteError>;
+
+    /// Merges phase gadgets acting on the same legs until none remain, returning the number of fusions
+    fn fuse_gadgets_all(&mut self) -> usize;
+}
+
+pub trait Simplify {
+    /// Converts the diagram to graph-like form and removes interior Clifford spiders
+    fn interior_clifford_simp(&mut self) -> SimplifyReport;
+
+    /// Like [`Simplify::interior_clifford_simp`], but also pivots away Pauli spiders on boundaries
+    fn clifford_simp(&mut self) -> SimplifyReport;
+
+    /// Like [`Simplify::clifford_simp`], but also pivots non-Pauli spiders into phase gadgets and
+    /// fuses gadgets acting on the same legs
+    fn full_reduce(&mut self) -> SimplifyReport;
+
+    /// Simplifies the diagram with `strategy`, recording every rewrite applied
+    fn trace(&mut self, strategy: Strategy) -> Derivation;
+}
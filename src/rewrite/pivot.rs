@@ -0,0 +1,414 @@
+use crate::graph::phase::Phase;
+use crate::graph::{Coords, EdgeType, Graph, Vertex, VertexBuilder, VertexIndex, VertexType};
+use crate::rewrite::lcomp::toggle_hadamard_edge;
+use crate::rewrite::{Pivot, RewriteError};
+use petgraph::prelude::EdgeRef;
+
+impl Pivot for Graph {
+    /// Returns every pair of adjacent interior Pauli Z spiders that pivoting can remove
+    fn find_pivot_candidates(&self) -> Vec<(VertexIndex, VertexIndex)> {
+        find_pairs(self, |graph, u, v| pivot_neighbours(graph, u, v).is_ok())
+    }
+
+    /// Rewrite: removes the adjacent interior Pauli Z spiders `u` and `v` by pivoting
+    ///
+    /// Both spiders must only be joined to other Z spiders, each by a single `Hadamard` edge, and
+    /// neither may be the hub of a phase gadget. The remaining neighbours are split into those of
+    /// `u` only, those of `v` only, and those shared by both; the `Hadamard` edges between each
+    /// pair of groups are complemented. Neighbours of `u` only gain the phase of `v`, neighbours
+    /// of `v` only gain the phase of `u`, and shared neighbours gain both plus `π`.
+    fn pivot(&mut self, u: VertexIndex, v: VertexIndex) -> Result<(), RewriteError> {
+        let (u_neighbours, v_neighbours) = pivot_neighbours(self, u, v)?;
+        apply_pivot(self, u, v, u_neighbours, v_neighbours);
+        Ok(())
+    }
+
+    /// Rewrite: applies pivoting until no candidates remain
+    fn pivot_all(&mut self) -> usize {
+        let mut count = 0;
+        while let Some(&(u, v)) = self.find_pivot_candidates().first() {
+            self.pivot(u, v).expect("candidate satisfies the pivot preconditions");
+            count += 1;
+        }
+        count
+    }
+
+    /// Returns every interior Pauli Z spider paired with an adjacent Pauli Z spider on a boundary
+    fn find_boundary_pivot_candidates(&self) -> Vec<(VertexIndex, VertexIndex)> {
+        find_pairs(self, |graph, u, v| boundary_pivot_match(graph, u, v).is_ok())
+    }
+
+    /// Rewrite: pivots the interior Pauli Z spider `u` with the Pauli Z spider `v` on a boundary
+    ///
+    /// `v` must be attached to exactly one boundary, and otherwise satisfy the same conditions as
+    /// [`Pivot::pivot`]. A phase-free Z spider is first inserted between `v` and its boundary,
+    /// which then takes the place of `v` on the boundary once the pivot is applied.
+    fn pivot_boundary(&mut self, u: VertexIndex, v: VertexIndex) -> Result<(), RewriteError> {
+        let (boundary, u_neighbours, mut v_neighbours) = boundary_pivot_match(self, u, v)?;
+        v_neighbours.push(unfuse_boundary(self, v, boundary));
+        apply_pivot(self, u, v, u_neighbours, v_neighbours);
+        Ok(())
+    }
+
+    /// Rewrite: applies boundary pivoting until no candidates remain
+    fn pivot_boundary_all(&mut self) -> usize {
+        let mut count = 0;
+        while let Some(&(u, v)) = self.find_boundary_pivot_candidates().first() {
+            self.pivot_boundary(u, v).expect("candidate satisfies the boundary pivot preconditions");
+            count += 1;
+        }
+        count
+    }
+
+    /// Returns every interior Pauli Z spider paired with an adjacent interior non-Pauli Z spider
+    fn find_gadget_pivot_candidates(&self) -> Vec<(VertexIndex, VertexIndex)> {
+        find_pairs(self, |graph, u, v| gadget_pivot_match(graph, u, v).is_ok())
+    }
+
+    /// Rewrite: pivots the interior Pauli Z spider `u` with the interior non-Pauli Z spider `v`
+    ///
+    /// The phase of `v` is first moved onto a new phase gadget: a phase-free hub joined to `v`
+    /// by a `Hadamard` edge, and an arity-1 leaf carrying the phase joined to the hub by a
+    /// `Hadamard` edge. Pivoting then removes `u` and `v`, leaving the hub joined to the former
+    /// neighbours of `u`.
+    fn pivot_gadget(&mut self, u: VertexIndex, v: VertexIndex) -> Result<(), RewriteError> {
+        let (u_neighbours, mut v_neighbours) = gadget_pivot_match(self, u, v)?;
+        v_neighbours.push(gadgetize(self, v));
+        apply_pivot(self, u, v, u_neighbours, v_neighbours);
+        Ok(())
+    }
+
+    /// Rewrite: applies gadget pivoting until no candidates remain
+    fn pivot_gadget_all(&mut self) -> usize {
+        let mut count = 0;
+        while let Some(&(u, v)) = self.find_gadget_pivot_candidates().first() {
+            self.pivot_gadget(u, v).expect("candidate satisfies the gadget pivot preconditions");
+            count += 1;
+        }
+        count
+    }
+}
+
+/// Returns every ordered pair of adjacent vertices accepted by `matches`.
+fn find_pairs(
+    graph: &Graph,
+    matches: impl Fn(&Graph, VertexIndex, VertexIndex) -> bool,
+) -> Vec<(VertexIndex, VertexIndex)> {
+    graph.enumerate_vertices()
+        .flat_map(|(u, _)| graph.neighbours(u).map(move |v| (u, v)))
+        .filter(|&(u, v)| matches(graph, u, v))
+        .collect()
+}
+
+/// Returns the neighbours of `u` other than `v` and of `v` other than `u`, failing unless both
+/// are interior Pauli Z spiders joined by a `Hadamard` edge.
+fn pivot_neighbours(
+    graph: &Graph,
+    u: VertexIndex,
+    v: VertexIndex,
+) -> Result<(Vec<VertexIndex>, Vec<VertexIndex>), RewriteError> {
+    let error = || RewriteError::NotPivotCandidate(u.index(), v.index());
+    if !is_pauli(graph, u)? || !is_pauli(graph, v)? {
+        return Err(error());
+    }
+    let u_neighbours = interior_neighbours(graph, u, v).ok_or_else(error)?;
+    let v_neighbours = interior_neighbours(graph, v, u).ok_or_else(error)?;
+    Ok((u_neighbours, v_neighbours))
+}
+
+/// Returns the boundary of `v` and the neighbours of `u` and `v`, failing unless `u` is an
+/// interior Pauli Z spider and `v` is a Pauli Z spider on exactly one boundary.
+fn boundary_pivot_match(
+    graph: &Graph,
+    u: VertexIndex,
+    v: VertexIndex,
+) -> Result<(VertexIndex, Vec<VertexIndex>, Vec<VertexIndex>), RewriteError> {
+    let error = || RewriteError::NotPivotCandidate(u.index(), v.index());
+    if !is_pauli(graph, u)? || !is_pauli(graph, v)? {
+        return Err(error());
+    }
+    let u_neighbours = interior_neighbours(graph, u, v).ok_or_else(error)?;
+
+    let mut boundaries = graph.neighbours(v).filter(|&neighbour| graph.is_boundary(neighbour));
+    let boundary = boundaries.next().ok_or_else(error)?;
+    if boundaries.next().is_some() {
+        return Err(error());
+    }
+    let v_neighbours = spider_neighbours(graph, v, u, Some(boundary)).ok_or_else(error)?;
+    Ok((boundary, u_neighbours, v_neighbours))
+}
+
+/// Returns the neighbours of `u` and `v`, failing unless `u` is an interior Pauli Z spider and
+/// `v` is an interior non-Pauli Z spider that is neither a leaf nor the hub of a phase gadget.
+fn gadget_pivot_match(
+    graph: &Graph,
+    u: VertexIndex,
+    v: VertexIndex,
+) -> Result<(Vec<VertexIndex>, Vec<VertexIndex>), RewriteError> {
+    let error = || RewriteError::NotPivotCandidate(u.index(), v.index());
+    if !is_pauli(graph, u)? || is_pauli(graph, v)? || graph.degree(v) < 2 {
+        return Err(error());
+    }
+    let u_neighbours = interior_neighbours(graph, u, v).ok_or_else(error)?;
+    let v_neighbours = interior_neighbours(graph, v, u).ok_or_else(error)?;
+    Ok((u_neighbours, v_neighbours))
+}
+
+/// Returns true if `index` is a Z spider with constant phase `0` or `π`, and false for other
+/// spiders.
+fn is_pauli(graph: &Graph, index: VertexIndex) -> Result<bool, RewriteError> {
+    let vertex = graph.vertex(index).ok_or(RewriteError::MissingVertex(index.index()))?;
+    let phase = vertex.phase();
+    Ok(vertex.vertex_type() == VertexType::Z && phase.is_constant() && phase.is_pauli())
+}
+
+/// Returns the neighbours of the interior spider `v` other than `partner`.
+fn interior_neighbours(graph: &Graph, v: VertexIndex, partner: VertexIndex) -> Option<Vec<VertexIndex>> {
+    spider_neighbours(graph, v, partner, None)
+}
+
+/// Returns the neighbours of `v` other than `partner` and `boundary`, or `None` unless `v` is
+/// joined to `partner` and every other neighbour is a distinct Z spider, each by a single
+/// `Hadamard` edge, and none of them is an arity-1 leaf.
+fn spider_neighbours(
+    graph: &Graph,
+    v: VertexIndex,
+    partner: VertexIndex,
+    boundary: Option<VertexIndex>,
+) -> Option<Vec<VertexIndex>> {
+    let mut neighbours = Vec::with_capacity(graph.degree(v));
+    let mut joined = false;
+    for edge in graph.incident_edges(v) {
+        let neighbour = edge.target();
+        if Some(neighbour) == boundary {
+            continue;
+        }
+        let spider = graph.vertex(neighbour).is_some_and(|n| n.vertex_type() == VertexType::Z);
+        if !spider || neighbour == v || *edge.weight() != EdgeType::Hadamard
+            || neighbours.contains(&neighbour) || graph.degree(neighbour) < 2 {
+            return None;
+        }
+        if neighbour == partner {
+            if joined {
+                return None;
+            }
+            joined = true;
+        } else {
+            neighbours.push(neighbour);
+        }
+    }
+    joined.then_some(neighbours)
+}
+
+/// Pivots along the edge between `u` and `v`, given their other neighbours, and removes both.
+///
+/// With *a* neighbours of `u` only, *b* of `v` only and *c* shared, the scalar is multiplied by
+/// `√2^(ab + ac + bc - a - b - 2c + 1)`, and by `-1` if both spiders have phase `π`, before the
+/// edges that already existed cancel.
+fn apply_pivot(
+    graph: &mut Graph,
+    u: VertexIndex,
+    v: VertexIndex,
+    u_neighbours: Vec<VertexIndex>,
+    v_neighbours: Vec<VertexIndex>,
+) {
+    let u_phase = graph.vertex(u).expect("vertex was checked above").phase();
+    let v_phase = graph.vertex(v).expect("vertex was checked above").phase();
+    graph.remove_vertex(u);
+    graph.remove_vertex(v);
+
+    let (shared, u_only): (Vec<VertexIndex>, Vec<VertexIndex>) = u_neighbours.into_iter()
+        .partition(|neighbour| v_neighbours.contains(neighbour));
+    let v_only: Vec<VertexIndex> = v_neighbours.into_iter()
+        .filter(|neighbour| !shared.contains(neighbour))
+        .collect();
+
+    for (left, right) in [(&u_only, &v_only), (&u_only, &shared), (&v_only, &shared)] {
+        for &a in left {
+            for &b in right {
+                toggle_hadamard_edge(graph, a, b);
+            }
+        }
+    }
+
+    let (a, b, c) = (u_only.len() as i32, v_only.len() as i32, shared.len() as i32);
+    let scalar = graph.scalar_mut();
+    scalar.mul_sqrt2_power(a * b + a * c + b * c - (a + b + 2 * c - 1));
+    if u_phase == Phase::one() && v_phase == Phase::one() {
+        scalar.mul_phase(Phase::one());
+    }
+
+    let shared_phase = u_phase.clone() + v_phase.clone() + Phase::one();
+    let groups = [(u_only, v_phase), (v_only, u_phase), (shared, shared_phase)];
+    for (group, phase) in groups {
+        for neighbour in group {
+            let vertex = graph.vertex_mut(neighbour).expect("neighbour exists");
+            vertex.set_phase(vertex.phase() + phase.clone());
+        }
+    }
+}
+
+/// Inserts a phase-free Z spider between `v` and `boundary`, returning its index.
+fn unfuse_boundary(graph: &mut Graph, v: VertexIndex, boundary: VertexIndex) -> VertexIndex {
+    let edge = graph.edge_index(v, boundary).expect("boundary is attached");
+    let edge_type = graph.edge_type(v, boundary).expect("boundary is attached");
+    let coords = midpoint(graph, v, boundary);
+    graph.remove_edge_at(edge);
+
+    let spider = graph.add_vertex(spider_at(Phase::zero(), coords));
+    graph.add_edge_of_type(v, spider, EdgeType::Hadamard).expect("spider exists");
+    graph.add_edge_of_type(spider, boundary, edge_type.toggled()).expect("boundary exists");
+    spider
+}
+
+/// Moves the phase of `v` onto a new phase gadget attached to `v`, returning the index of its hub.
+fn gadgetize(graph: &mut Graph, v: VertexIndex) -> VertexIndex {
+    let vertex = graph.vertex_mut(v).expect("vertex was checked above");
+    let phase = vertex.phase();
+    vertex.set_phase(Phase::zero());
+    let coords = vertex.coords();
+
+    let hub = graph.add_vertex(spider_at(Phase::zero(), coords));
+    let leaf_coords = coords.map(|coords| Coords { x: coords.x, y: coords.y - 0.5 });
+    let leaf = graph.add_vertex(spider_at(phase, leaf_coords));
+    graph.add_edge_of_type(v, hub, EdgeType::Hadamard).expect("spider exists");
+    graph.add_edge_of_type(hub, leaf, EdgeType::Hadamard).expect("hub exists");
+    hub
+}
+
+/// Builds a Z spider with `phase`, positioned at `coords` if given.
+fn spider_at(phase: Phase, coords: Option<Coords>) -> Vertex {
+    let mut vertex = VertexBuilder::z().phase(phase).build();
+    if let Some(coords) = coords {
+        vertex.set_coords(coords);
+    }
+    vertex
+}
+
+/// Returns the point halfway between `a` and `b`, if both are positioned.
+fn midpoint(graph: &Graph, a: VertexIndex, b: VertexIndex) -> Option<Coords> {
+    let a = graph.vertex(a)?.coords()?;
+    let b = graph.vertex(b)?.coords()?;
+    Some(Coords { x: (a.x + b.x) / 2.0, y: (a.y + b.y) / 2.0 })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::phase::Phase;
+    use crate::graph::{EdgeType, Graph, VertexBuilder, VertexIndex};
+    use crate::rewrite::fixtures::output_spiders;
+    use crate::rewrite::{Pivot, RewriteError};
+    use crate::tensor::Evaluate;
+
+    /// Builds adjacent spiders `u` and `v` with the given phases, where `u` is joined to `a` and
+    /// `c`, `v` is joined to `b` and `c`, and `a`, `b` and `c` are each attached to an output.
+    fn pair(u_phase: Phase, v_phase: Phase) -> (Graph, VertexIndex, VertexIndex, [VertexIndex; 3]) {
+        let (mut graph, outer) = output_spiders();
+        let u = graph.add_vertex(VertexBuilder::z().phase(u_phase).build());
+        let v = graph.add_vertex(VertexBuilder::z().phase(v_phase).build());
+        graph.add_edge_of_type(u, v, EdgeType::Hadamard).unwrap();
+
+        let [a, b, c] = outer;
+        graph.add_edge_of_type(u, a, EdgeType::Hadamard).unwrap();
+        graph.add_edge_of_type(u, c, EdgeType::Hadamard).unwrap();
+        graph.add_edge_of_type(v, b, EdgeType::Hadamard).unwrap();
+        graph.add_edge_of_type(v, c, EdgeType::Hadamard).unwrap();
+        (graph, u, v, outer)
+    }
+
+    #[test]
+    fn pivot_complements_neighbourhoods() {
+        let (mut graph, u, v, [a, b, c]) = pair(Phase::one(), Phase::zero());
+        graph.add_edge_of_type(a, b, EdgeType::Hadamard).unwrap();
+        assert_eq!(graph.find_pivot_candidates(), [(u, v), (v, u)]);
+
+        let expected = graph.to_matrix();
+        graph.pivot(u, v).unwrap();
+        assert!(graph.to_matrix().approx_eq(&expected, 1e-9));
+        assert!(graph.vertex(u).is_none());
+        assert!(graph.vertex(v).is_none());
+        assert_eq!(graph.edge_type(a, b), None);
+        assert_eq!(graph.edge_type(a, c), Some(EdgeType::Hadamard));
+        assert_eq!(graph.edge_type(b, c), Some(EdgeType::Hadamard));
+        assert_eq!(graph.vertex(a).unwrap().phase(), Phase::zero());
+        assert_eq!(graph.vertex(b).unwrap().phase(), Phase::one());
+        assert_eq!(graph.vertex(c).unwrap().phase(), Phase::zero());
+    }
+
+    #[test]
+    fn pivot_tracks_scalar() {
+        for (u_phase, v_phase) in [(Phase::zero(), Phase::zero()), (Phase::one(), Phase::one())] {
+            let (mut graph, u, v, [a, _, c]) = pair(u_phase, v_phase);
+            graph.add_edge_of_type(a, c, EdgeType::Hadamard).unwrap();
+            let expected = graph.to_matrix();
+            graph.pivot(u, v).unwrap();
+            assert!(graph.to_matrix().approx_eq(&expected, 1e-9));
+        }
+    }
+
+    #[test]
+    fn pivot_rejects_non_pauli_and_boundary_spiders() {
+        let (mut graph, u, v, [a, _, _]) = pair(Phase::zero(), Phase::plus());
+        assert!(graph.find_pivot_candidates().is_empty());
+        assert!(matches!(graph.pivot(u, v), Err(RewriteError::NotPivotCandidate(_, _))));
+        assert!(matches!(graph.pivot(u, a), Err(RewriteError::NotPivotCandidate(_, _))));
+    }
+
+    #[test]
+    fn pivot_boundary_moves_boundary_to_new_spider() {
+        let mut graph = Graph::new(2);
+        let outputs = [0, 1].map(|qubit| {
+            let output = graph.add_vertex(VertexBuilder::b().build());
+            graph.set_output(qubit, output).unwrap();
+            output
+        });
+        let v = graph.add_vertex(VertexBuilder::z().build());
+        let u = graph.add_vertex(VertexBuilder::z().phase(Phase::one()).build());
+        let w = graph.add_vertex(VertexBuilder::z().build());
+        graph.add_edge(v, outputs[0]).unwrap();
+        graph.add_edge_of_type(v, u, EdgeType::Hadamard).unwrap();
+        graph.add_edge_of_type(u, w, EdgeType::Hadamard).unwrap();
+        graph.add_edge(w, outputs[1]).unwrap();
+
+        assert!(graph.find_pivot_candidates().is_empty());
+        let candidates = graph.find_boundary_pivot_candidates();
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.contains(&(u, v)) && candidates.contains(&(u, w)));
+
+        let expected = graph.to_matrix();
+        graph.pivot_boundary(u, v).unwrap();
+        assert!(graph.to_matrix().approx_eq(&expected, 1e-9));
+        assert_eq!(graph.num_vertices(), 4);
+        assert!(graph.find_boundary_pivot_candidates().is_empty());
+
+        let spider = graph.neighbours(outputs[0]).next().unwrap();
+        assert_eq!(graph.edge_type(spider, outputs[0]), Some(EdgeType::Hadamard));
+        assert_eq!(graph.edge_type(spider, w), Some(EdgeType::Hadamard));
+        assert_eq!(graph.vertex(spider).unwrap().phase(), Phase::one());
+        assert_eq!(graph.vertex(w).unwrap().phase(), Phase::zero());
+    }
+
+    #[test]
+    fn pivot_gadget_extracts_phase_gadget() {
+        let (mut graph, u, v, [a, _, c]) = pair(Phase::one(), Phase::plus());
+        assert_eq!(graph.find_gadget_pivot_candidates(), [(u, v)]);
+
+        let (vertices, expected) = (graph.num_vertices(), graph.to_matrix());
+        graph.pivot_gadget(u, v).unwrap();
+        assert!(graph.to_matrix().approx_eq(&expected, 1e-9));
+        assert_eq!(graph.num_vertices(), vertices);
+        assert!(graph.find_gadget_pivot_candidates().is_empty());
+
+        let leaf = graph.enumerate_vertices()
+            .find(|(_, vertex)| vertex.phase() == Phase::plus())
+            .map(|(index, _)| index)
+            .unwrap();
+        assert_eq!(graph.degree(leaf), 1);
+        let hub = graph.neighbours(leaf).next().unwrap();
+        assert_eq!(graph.edge_type(hub, leaf), Some(EdgeType::Hadamard));
+        assert_eq!(graph.vertex(hub).unwrap().phase(), Phase::one());
+        assert_eq!(graph.edge_type(hub, a), Some(EdgeType::Hadamard));
+        assert_eq!(graph.edge_type(hub, c), Some(EdgeType::Hadamard));
+    }
+}
@@ -0,0 +1,568 @@
+use crate::graph::phase::Phase;
+use crate::graph::{Coords, EdgeType, Graph, VertexIndex, VertexType};
+use crate::rewrite::RewriteError;
+use num_complex::Complex64;
+use petgraph::prelude::{EdgeIndex, EdgeRef};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+type PhasePredicate = Box<dyn Fn(Phase) -> bool>;
+type PhaseExpression = Box<dyn Fn(&HashMap<VertexIndex, Phase>) -> Phase>;
+
+/// Side of a diagram a boundary sits on, together with its qubit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BoundaryKey {
+    Input(usize),
+    Output(usize),
+}
+
+/// A rewrite rule given by two diagrams
+///
+/// The inputs and outputs of the left-hand side stand for wires leaving the matched part of a
+/// diagram, and are reattached to the right-hand side at the boundaries with the same qubit.
+/// Every other vertex of the left-hand side, its interior, must match a vertex of the same type
+/// with no edges besides those of the pattern.
+///
+/// Reattaching a wire does not keep its stored direction, so neither side may contain a Y
+/// spider and no wire leaving a match may end at one, see [`VertexType::Y`].
+///
+/// By default an interior vertex only matches vertices with the same phase, and the vertices of
+/// the right-hand side keep their own phases. Both can be replaced by
+/// [`Rule::with_predicate`] and [`Rule::with_phase`].
+///
+/// The scalars of both sides are part of the rule: applying it multiplies the scalar of the
+/// diagram by the scalar of the right-hand side over that of the left-hand side, so a rule whose
+/// sides evaluate to the same map leaves the map of the diagram unchanged.
+pub struct Rule {
+    lhs: Graph,
+    rhs: Graph,
+    lhs_boundaries: HashMap<VertexIndex, BoundaryKey>,
+    rhs_boundaries: HashMap<VertexIndex, BoundaryKey>,
+    /// Interior vertices of the left-hand side, each adjacent to an earlier one where possible
+    order: Vec<VertexIndex>,
+    predicates: HashMap<VertexIndex, PhasePredicate>,
+    phases: HashMap<VertexIndex, PhaseExpression>,
+}
+
+/// An embedding of the interior of a rule's left-hand side into a diagram
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    vertices: HashMap<VertexIndex, VertexIndex>,
+}
+
+impl Match {
+    /// Returns the vertex matched by the left-hand side vertex `vertex`.
+    pub fn vertex(&self, vertex: VertexIndex) -> Option<VertexIndex> {
+        self.vertices.get(&vertex).copied()
+    }
+
+    /// Returns every matched vertex of the diagram.
+    pub fn vertices(&self) -> impl Iterator<Item = VertexIndex> + '_ {
+        self.vertices.values().copied()
+    }
+}
+
+impl Rule {
+    /// Creates the rule rewriting `lhs` into `rhs`.
+    ///
+    /// Fails unless both sides have the same inputs and outputs, every boundary has exactly one
+    /// edge, no boundary of `lhs` is joined to another boundary, `lhs` has an interior vertex and
+    /// a non-zero scalar, and neither side has a Y spider.
+    pub fn new(lhs: Graph, rhs: Graph) -> Result<Self, RewriteError> {
+        if let Some((index, _)) = lhs.enumerate_vertices().chain(rhs.enumerate_vertices())
+            .find(|(_, vertex)| vertex.vertex_type() == VertexType::Y) {
+            return Err(RewriteError::RuleYSpider(index.index()));
+        }
+        if lhs.scalar().factor().is_some_and(|factor| factor == Complex64::ZERO) {
+            return Err(RewriteError::RuleZeroScalar);
+        }
+        let lhs_boundaries = boundary_keys(&lhs)?;
+        let rhs_boundaries = boundary_keys(&rhs)?;
+        let lhs_keys: HashSet<&BoundaryKey> = lhs_boundaries.values().collect();
+        let rhs_keys: HashSet<&BoundaryKey> = rhs_boundaries.values().collect();
+        if lhs_keys != rhs_keys {
+            return Err(RewriteError::RuleBoundaryMismatch);
+        }
+        if let Some(&boundary) = lhs_boundaries.keys()
+            .find(|&&boundary| lhs.neighbours(boundary).any(|neighbour| lhs.is_boundary(neighbour))) {
+            return Err(RewriteError::RuleBoundary(boundary.index()));
+        }
+
+        let order = interior_order(&lhs);
+        if order.is_empty() {
+            return Err(RewriteError::EmptyRule);
+        }
+        Ok(Rule {
+            lhs,
+            rhs,
+            lhs_boundaries,
+            rhs_boundaries,
+            order,
+            predicates: HashMap::new(),
+            phases: HashMap::new(),
+        })
+    }
+
+    /// Matches the left-hand side vertex `vertex` only against vertices whose phase satisfies
+    /// `predicate`.
+    ///
+    /// Fails with [`RewriteError::NotInterior`] if `vertex` is an input or output.
+    pub fn with_predicate(mut self, vertex: VertexIndex, predicate: impl Fn(Phase) -> bool + 'static) -> Result<Self, RewriteError> {
+        if self.lhs.vertex(vertex).is_none() {
+            return Err(RewriteError::MissingVertex(vertex.index()));
+        }
+        if self.lhs_boundaries.contains_key(&vertex) {
+            return Err(RewriteError::NotInterior(vertex.index()));
+        }
+        self.predicates.insert(vertex, Box::new(predicate));
+        Ok(self)
+    }
+
+    /// Gives the right-hand side vertex `vertex` the phase computed by `phase` from the phases
+    /// matched by each interior vertex of the left-hand side.
+    ///
+    /// Fails with [`RewriteError::NotInterior`] if `vertex` is an input or output.
+    pub fn with_phase(
+        mut self,
+        vertex: VertexIndex,
+        phase: impl Fn(&HashMap<VertexIndex, Phase>) -> Phase + 'static,
+    ) -> Result<Self, RewriteError> {
+        if self.rhs.vertex(vertex).is_none() {
+            return Err(RewriteError::MissingVertex(vertex.index()));
+        }
+        if self.rhs_boundaries.contains_key(&vertex) {
+            return Err(RewriteError::NotInterior(vertex.index()));
+        }
+        self.phases.insert(vertex, Box::new(phase));
+        Ok(self)
+    }
+
+    /// Returns every embedding of the left-hand side into `graph`.
+    ///
+    /// Runs in exponential time in the size of the left-hand side in the worst case.
+    pub fn find_matches(&self, graph: &Graph) -> Vec<Match> {
+        let mut matches = Vec::new();
+        self.extend(graph, &mut HashMap::new(), &mut matches, false);
+        matches
+    }
+
+    /// Returns some embedding of the left-hand side into `graph`, if there is one.
+    pub fn find_match(&self, graph: &Graph) -> Option<Match> {
+        let mut matches = Vec::new();
+        self.extend(graph, &mut HashMap::new(), &mut matches, true);
+        matches.pop()
+    }
+
+    /// Rewrite: replaces the part of `graph` matched by `found` with the right-hand side
+    ///
+    /// The matched vertices are removed, a copy of the interior of the right-hand side is added,
+    /// and every wire that left the match through a boundary is reattached to the vertex next to
+    /// the same boundary of the right-hand side. New vertices are moved so that their centre lies
+    /// on the centre of the removed vertices.
+    ///
+    /// Fails with [`RewriteError::StaleMatch`] if `found` is no longer an embedding in `graph`.
+    pub fn apply(&self, graph: &mut Graph, found: &Match) -> Result<(), RewriteError> {
+        let wires = self.wires(graph, &found.vertices).ok_or(RewriteError::StaleMatch)?;
+        let phases: HashMap<VertexIndex, Phase> = found.vertices.iter()
+            .map(|(&lhs, &host)| (lhs, graph.vertex(host).expect("matched vertex exists").phase()))
+            .collect();
+        let (centre, offset) = self.placement(graph, &found.vertices);
+        for &host in found.vertices.values() {
+            graph.remove_vertex(host);
+        }
+        *graph.scalar_mut() *= self.rhs.scalar().clone() * self.lhs.scalar().inverse();
+
+        let mut added = HashMap::new();
+        for (index, vertex) in self.rhs.enumerate_vertices() {
+            if self.rhs_boundaries.contains_key(&index) {
+                continue;
+            }
+            let mut vertex = vertex.clone();
+            if let Some(phase) = self.phases.get(&index) {
+                vertex.set_phase(phase(&phases));
+            }
+            match (vertex.coords(), offset) {
+                (Some(coords), Some(offset)) => vertex.set_coords(Coords { x: coords.x + offset.x, y: coords.y + offset.y }),
+                (None, _) => if let Some(centre) = centre { vertex.set_coords(centre) },
+                _ => {}
+            }
+            added.insert(index, graph.add_vertex(vertex));
+        }
+
+        // Each right-hand side boundary stands for the outside end of the wire it replaces
+        let outside: HashMap<BoundaryKey, VertexIndex> = wires.into_iter().collect();
+        let endpoint = |index: VertexIndex| match self.rhs_boundaries.get(&index) {
+            Some(key) => outside[key],
+            None => added[&index],
+        };
+        for edge in self.rhs.enumerate_edges() {
+            graph.add_edge_of_type(endpoint(edge.source()), endpoint(edge.target()), *edge.weight())
+                .expect("endpoints exist");
+        }
+        Ok(())
+    }
+
+    /// Rewrite: applies the rule until it no longer matches or has been applied `limit` times,
+    /// returning the number of applications
+    ///
+    /// A rule whose right-hand side contains a match of its left-hand side, such as one that
+    /// only moves a phase or swaps two spiders, can match its own output forever. `limit` bounds
+    /// the number of applications for such rules.
+    pub fn apply_all(&self, graph: &mut Graph, limit: usize) -> usize {
+        let mut count = 0;
+        while count < limit {
+            let Some(found) = self.find_match(graph) else { break };
+            self.apply(graph, &found).expect("match was just found");
+            count += 1;
+        }
+        count
+    }
+
+    /// Extends the partial embedding `mapping` in every possible way, collecting the complete
+    /// embeddings into `matches`. Stops after the first one if `first` is set.
+    fn extend(
+        &self,
+        graph: &Graph,
+        mapping: &mut HashMap<VertexIndex, VertexIndex>,
+        matches: &mut Vec<Match>,
+        first: bool,
+    ) {
+        let Some(&vertex) = self.order.get(mapping.len()) else {
+            if self.wires(graph, mapping).is_some() {
+                matches.push(Match { vertices: mapping.clone() });
+            }
+            return;
+        };
+
+        let anchor = self.lhs.neighbours(vertex).find_map(|neighbour| mapping.get(&neighbour).copied());
+        let candidates: Vec<VertexIndex> = match anchor {
+            Some(anchor) => {
+                let mut candidates: Vec<VertexIndex> = graph.neighbours(anchor).collect();
+                candidates.sort();
+                candidates.dedup();
+                candidates
+            }
+            None => graph.enumerate_vertices().map(|(index, _)| index).collect(),
+        };
+
+        for candidate in candidates {
+            if mapping.values().any(|&used| used == candidate) || !self.accepts(graph, mapping, vertex, candidate) {
+                continue;
+            }
+            mapping.insert(vertex, candidate);
+            self.extend(graph, mapping, matches, first);
+            mapping.remove(&vertex);
+            if first && !matches.is_empty() {
+                return;
+            }
+        }
+    }
+
+    /// Returns true if `candidate` can be matched by the interior vertex `vertex`, given the
+    /// vertices matched so far.
+    fn accepts(
+        &self,
+        graph: &Graph,
+        mapping: &HashMap<VertexIndex, VertexIndex>,
+        vertex: VertexIndex,
+        candidate: VertexIndex,
+    ) -> bool {
+        let pattern = self.lhs.vertex(vertex).expect("interior vertex exists");
+        let Some(host) = graph.vertex(candidate) else { return false };
+        let phase_ok = match self.predicates.get(&vertex) {
+            Some(predicate) => predicate(host.phase()),
+            None => host.phase() == pattern.phase(),
+        };
+        if host.vertex_type() != pattern.vertex_type() || !phase_ok || graph.degree(candidate) != self.lhs.degree(vertex) {
+            return false;
+        }
+
+        mapping.iter()
+            .map(|(&other, &image)| (other, image))
+            .chain([(vertex, candidate)])
+            .all(|(other, image)| edge_types(&self.lhs, vertex, other) == edge_types(graph, candidate, image))
+    }
+
+    /// Returns the outside end of the wire leaving `graph` at each boundary of the left-hand
+    /// side, or `None` if the wires do not match those of the pattern.
+    fn wires(
+        &self,
+        graph: &Graph,
+        mapping: &HashMap<VertexIndex, VertexIndex>,
+    ) -> Option<Vec<(BoundaryKey, VertexIndex)>> {
+        if mapping.len() != self.order.len() {
+            return None;
+        }
+        let images: HashSet<VertexIndex> = mapping.values().copied().collect();
+
+        let mut wires = Vec::with_capacity(self.lhs_boundaries.len());
+        for (&vertex, &image) in mapping {
+            if !self.accepts(graph, mapping, vertex, image) {
+                return None;
+            }
+
+            let mut outside: Vec<(EdgeIndex, VertexIndex, EdgeType)> = graph.incident_edges(image)
+                .filter(|edge| !images.contains(&edge.target()))
+                .map(|edge| (edge.id(), edge.target(), *edge.weight()))
+                .collect();
+            if outside.iter().any(|&(_, end, _)| graph.vertex(end).is_some_and(|vertex| vertex.vertex_type() == VertexType::Y)) {
+                return None;
+            }
+            for edge in self.lhs.incident_edges(vertex) {
+                let Some(&key) = self.lhs_boundaries.get(&edge.target()) else { continue };
+                let position = outside.iter().position(|&(_, _, edge_type)| edge_type == *edge.weight())?;
+                let (_, end, _) = outside.swap_remove(position);
+                wires.push((key, end));
+            }
+            if !outside.is_empty() {
+                return None;
+            }
+        }
+        Some(wires)
+    }
+
+    /// Returns the centre of the matched vertices, and the shift moving the interior of the
+    /// right-hand side onto it, where every vertex involved is positioned.
+    fn placement(&self, graph: &Graph, mapping: &HashMap<VertexIndex, VertexIndex>) -> (Option<Coords>, Option<Coords>) {
+        let host = centre(mapping.values().map(|&index| graph.vertex(index).and_then(|vertex| vertex.coords())));
+        let rhs = centre(self.rhs.enumerate_vertices()
+            .filter(|(index, _)| !self.rhs_boundaries.contains_key(index))
+            .map(|(_, vertex)| vertex.coords()));
+        let offset = host.zip(rhs).map(|(host, rhs)| Coords { x: host.x - rhs.x, y: host.y - rhs.y });
+        (host, offset)
+    }
+}
+
+/// Returns the inputs and outputs of `graph`, failing unless each has exactly one edge.
+fn boundary_keys(graph: &Graph) -> Result<HashMap<VertexIndex, BoundaryKey>, RewriteError> {
+    let inputs = graph.input_qubits().map(|&qubit| (*graph.input_index(qubit).expect("input exists"), BoundaryKey::Input(qubit)));
+    let outputs = graph.output_qubits().map(|&qubit| (*graph.output_index(qubit).expect("output exists"), BoundaryKey::Output(qubit)));
+    let keys: HashMap<VertexIndex, BoundaryKey> = inputs.chain(outputs).collect();
+
+    let invalid = graph.enumerate_vertices()
+        .map(|(index, _)| index)
+        .find(|&index| graph.is_boundary(index) && (!keys.contains_key(&index) || graph.degree(index) != 1));
+    match invalid {
+        Some(index) => Err(RewriteError::RuleBoundary(index.index())),
+        None => Ok(keys),
+    }
+}
+
+/// Returns the interior vertices of `graph` in breadth-first order, so that each vertex after
+/// the first of its component is adjacent to an earlier one.
+fn interior_order(graph: &Graph) -> Vec<VertexIndex> {
+    let mut order = Vec::new();
+    let mut seen = HashSet::new();
+    for (start, _) in graph.enumerate_vertices() {
+        if graph.is_boundary(start) || !seen.insert(start) {
+            continue;
+        }
+        let mut queue = VecDeque::from([start]);
+        while let Some(vertex) = queue.pop_front() {
+            order.push(vertex);
+            for neighbour in graph.neighbours(vertex) {
+                if !graph.is_boundary(neighbour) && seen.insert(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+    }
+    order
+}
+
+/// Returns the number of `Simple` and `Hadamard` edges between `a` and `b`.
+fn edge_types(graph: &Graph, a: VertexIndex, b: VertexIndex) -> (usize, usize) {
+    graph.incident_edges(a)
+        .filter(|edge| edge.target() == b)
+        .fold((0, 0), |(simple, hadamard), edge| match edge.weight() {
+            EdgeType::Simple => (simple + 1, hadamard),
+            EdgeType::Hadamard => (simple, hadamard + 1),
+        })
+}
+
+/// Returns the mean of `points`, or `None` if any is missing or there are none.
+fn centre(points: impl Iterator<Item = Option<Coords>>) -> Option<Coords> {
+    let (mut x, mut y, mut count) = (0.0, 0.0, 0);
+    for point in points {
+        let point = point?;
+        x += point.x;
+        y += point.y;
+        count += 1;
+    }
+    (count > 0).then(|| Coords { x: x / count as f64, y: y / count as f64 })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, GraphBuilder};
+    use crate::graph::phase::Phase;
+    use crate::graph::{EdgeType, Graph, VertexBuilder, VertexIndex, VertexType};
+    use crate::rewrite::{RewriteError, Rule};
+    use crate::tensor::Evaluate;
+    use num_complex::Complex64;
+
+    /// Builds a single wire through Z spiders with the given phases.
+    fn chain(phases: &[Phase]) -> (Graph, Vec<VertexIndex>) {
+        let mut graph = Graph::new(1);
+        let spiders = phases.iter()
+            .enumerate()
+            .map(|(x, phase)| {
+                graph.add_vertex_along_wire(0, VertexBuilder::z().coords(x as f64, 0.0).phase(phase.clone()).build()).unwrap()
+            })
+            .collect();
+        graph.position_inputs();
+        graph.position_outputs();
+        (graph, spiders)
+    }
+
+    /// Fuses two adjacent Z spiders on a wire, summing their phases.
+    fn fusion_rule() -> Rule {
+        let (lhs, lhs_spiders) = chain(&[Phase::zero(), Phase::zero()]);
+        let (rhs, rhs_spiders) = chain(&[Phase::zero()]);
+        let [a, b] = [lhs_spiders[0], lhs_spiders[1]];
+        Rule::new(lhs, rhs).unwrap()
+            .with_predicate(a, |_| true).unwrap()
+            .with_predicate(b, |_| true).unwrap()
+            .with_phase(rhs_spiders[0], move |phases| phases[&a].clone() + phases[&b].clone()).unwrap()
+    }
+
+    #[test]
+    fn rule_fuses_spiders() {
+        let mut graph = GraphBuilder::z_plus(0).compose(&GraphBuilder::z_plus(0)).unwrap();
+        let rule = fusion_rule();
+        assert_eq!(rule.find_matches(&graph).len(), 2);
+
+        assert_eq!(rule.apply_all(&mut graph, usize::MAX), 1);
+        assert_eq!(graph.num_vertices(), 3);
+        assert_eq!(graph.num_edges(), 2);
+        let spider = graph.vertices()
+            .find(|vertex| vertex.vertex_type() == VertexType::Z)
+            .unwrap();
+        assert_eq!(spider.phase(), Phase::one());
+        assert!(spider.coords().is_some());
+    }
+
+    #[test]
+    fn rule_respects_types_and_predicates() {
+        let (lhs, _) = chain(&[Phase::zero()]);
+        let mut rhs = Graph::new(1);
+        rhs.add_wire_to(0).unwrap();
+        let identity = Rule::new(lhs, rhs).unwrap();
+
+        let mut graph = GraphBuilder::z_plus(0);
+        assert!(identity.find_match(&graph).is_none());
+        graph.vertices_mut()
+            .filter(|vertex| vertex.vertex_type() == VertexType::Z)
+            .for_each(|vertex| vertex.set_phase(Phase::zero()));
+        assert_eq!(identity.apply_all(&mut graph, usize::MAX), 1);
+        assert_eq!(graph.num_vertices(), 2);
+
+        let (input, output) = (*graph.input_index(0).unwrap(), *graph.output_index(0).unwrap());
+        assert_eq!(graph.edge_type(input, output), Some(EdgeType::Simple));
+
+        let mut graph = GraphBuilder::x_plus(0);
+        graph.vertices_mut().for_each(|vertex| vertex.set_phase(Phase::zero()));
+        assert!(identity.find_match(&graph).is_none());
+    }
+
+    #[test]
+    fn rule_multiplies_scalar_by_ratio_of_sides() {
+        // A Hadamard self-loop adds π to a Z spider and scales it by 1/√2
+        let (mut lhs, spiders) = chain(&[Phase::one()]);
+        lhs.add_edge_of_type(spiders[0], spiders[0], EdgeType::Hadamard).unwrap();
+        lhs.scalar_mut().mul_sqrt2_power(1);
+        let (rhs, _) = chain(&[Phase::zero()]);
+        let rule = Rule::new(lhs, rhs).unwrap();
+
+        let (mut graph, spiders) = chain(&[Phase::one()]);
+        graph.add_edge_of_type(spiders[0], spiders[0], EdgeType::Hadamard).unwrap();
+        let expected = graph.to_matrix();
+        assert_eq!(rule.apply_all(&mut graph, usize::MAX), 1);
+        assert_eq!(graph.scalar().sqrt2_power(), -1);
+        assert!(graph.to_matrix().approx_eq(&expected, 1e-9));
+    }
+
+    #[test]
+    fn rules_reject_zero_left_hand_scalar() {
+        let (mut lhs, _) = chain(&[Phase::zero()]);
+        lhs.scalar_mut().mul_factor(Complex64::ZERO);
+        let (rhs, _) = chain(&[Phase::zero()]);
+        assert!(matches!(Rule::new(lhs, rhs), Err(RewriteError::RuleZeroScalar)));
+    }
+
+    #[test]
+    fn rule_respects_edge_types() {
+        let mut graph = GraphBuilder::z_plus(0).compose(&GraphBuilder::z_plus(0)).unwrap();
+        let spiders: Vec<VertexIndex> = graph.enumerate_vertices()
+            .filter(|(_, vertex)| vertex.vertex_type() == VertexType::Z)
+            .map(|(index, _)| index)
+            .collect();
+        let edge = graph.edge_index(spiders[0], spiders[1]).unwrap();
+        *graph.edge_mut(edge).unwrap() = EdgeType::Hadamard;
+        assert!(fusion_rule().find_match(&graph).is_none());
+    }
+
+    #[test]
+    fn stale_matches_are_rejected() {
+        let mut graph = GraphBuilder::z_plus(0).compose(&GraphBuilder::z_plus(0)).unwrap();
+        let rule = fusion_rule();
+        let found = rule.find_match(&graph).unwrap();
+        rule.apply(&mut graph, &found).unwrap();
+        assert!(matches!(rule.apply(&mut graph, &found), Err(RewriteError::StaleMatch)));
+    }
+
+    #[test]
+    fn rules_reject_y_spiders() {
+        let (lhs, _) = chain(&[Phase::zero()]);
+        let mut rhs = Graph::new(1);
+        rhs.add_vertex_along_wire(0, VertexBuilder::y().build()).unwrap();
+        assert!(matches!(Rule::new(lhs, rhs), Err(RewriteError::RuleYSpider(_))));
+
+        let (lhs, _) = chain(&[Phase::zero()]);
+        let mut rhs = Graph::new(1);
+        rhs.add_wire_to(0).unwrap();
+        let identity = Rule::new(lhs, rhs).unwrap();
+        let mut graph = Graph::new(1);
+        graph.add_vertex_along_wire(0, VertexBuilder::y().build()).unwrap();
+        graph.add_vertex_along_wire(0, VertexBuilder::z().build()).unwrap();
+        assert!(identity.find_match(&graph).is_none());
+    }
+
+    #[test]
+    fn rule_options_reject_boundaries() {
+        let (lhs, _) = chain(&[Phase::zero()]);
+        let (rhs, _) = chain(&[Phase::zero()]);
+        let (lhs_input, rhs_output) = (*lhs.input_index(0).unwrap(), *rhs.output_index(0).unwrap());
+        let rule = Rule::new(lhs, rhs).unwrap();
+        assert!(matches!(rule.with_predicate(lhs_input, |_| true), Err(RewriteError::NotInterior(_))));
+
+        let (lhs, _) = chain(&[Phase::zero()]);
+        let (rhs, _) = chain(&[Phase::zero()]);
+        let rule = Rule::new(lhs, rhs).unwrap();
+        assert!(matches!(rule.with_phase(rhs_output, |_| Phase::zero()), Err(RewriteError::NotInterior(_))));
+    }
+
+    #[test]
+    fn apply_all_stops_at_limit() {
+        let (lhs, _) = chain(&[Phase::zero()]);
+        let (rhs, _) = chain(&[Phase::zero()]);
+        let rule = Rule::new(lhs, rhs).unwrap();
+
+        let (mut graph, _) = chain(&[Phase::zero()]);
+        assert_eq!(rule.apply_all(&mut graph, 3), 3);
+        assert_eq!(graph.num_vertices(), 3);
+    }
+
+    #[test]
+    fn rule_sides_must_share_boundaries() {
+        let (lhs, _) = chain(&[Phase::zero()]);
+        let rhs = Graph::new(1);
+        assert!(matches!(Rule::new(lhs, rhs), Err(RewriteError::RuleBoundaryMismatch)));
+
+        let mut lhs = Graph::new(1);
+        lhs.add_wire_to(0).unwrap();
+        let mut rhs = Graph::new(1);
+        rhs.add_wire_to(0).unwrap();
+        assert!(matches!(Rule::new(lhs, rhs), Err(RewriteError::RuleBoundary(_))));
+    }
+}
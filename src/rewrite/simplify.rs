@@ -0,0 +1,245 @@
+use crate::graph::{Graph, VertexIndex};
+use crate::rewrite::fusion::find_fusion;
+use crate::rewrite::gadget::find_gadget_fusion;
+use crate::rewrite::hopf::{find_hopf, find_self_loop};
+use crate::rewrite::identity::find_identity;
+use crate::rewrite::{
+    Derivation, LocalComplementation, Pivot, RewriteRule, RewriteStep, Simplify, SimplifyReport, Strategy,
+};
+
+impl Simplify for Graph {
+    /// Strategy: converts the diagram to graph-like form, then applies spider fusion, identity
+    /// removal, local complementation and pivoting until none of them applies
+    ///
+    /// Rewrites made while converting to graph-like form are not counted. Removing identities
+    /// may join boundaries directly, so the result is not always graph-like.
+    fn interior_clifford_simp(&mut self) -> SimplifyReport {
+        Simplifier::new(self, false).run(Strategy::InteriorClifford).report
+    }
+
+    /// Strategy: alternates [`Simplify::interior_clifford_simp`] with boundary pivoting until no
+    /// boundary pivot applies
+    fn clifford_simp(&mut self) -> SimplifyReport {
+        Simplifier::new(self, false).run(Strategy::Clifford).report
+    }
+
+    /// Strategy: alternates [`Simplify::clifford_simp`] with gadget fusion and gadget pivoting
+    /// until neither applies
+    ///
+    /// Every interior non-Clifford phase ends up on a phase gadget or on a spider next to a
+    /// boundary.
+    fn full_reduce(&mut self) -> SimplifyReport {
+        Simplifier::new(self, false).run(Strategy::FullReduce).report
+    }
+
+    /// Strategy: runs `strategy`, recording each rewrite as a step of the returned derivation
+    ///
+    /// The derivation starts from a clone of the diagram before simplification, and its first
+    /// step is the conversion to graph-like form.
+    fn trace(&mut self, strategy: Strategy) -> Derivation {
+        let initial = self.clone();
+        let steps = Simplifier::new(self, true).run(strategy).steps.unwrap_or_default();
+        Derivation { initial, steps }
+    }
+}
+
+/// Applies rewrites one at a time, counting them and optionally recording each as a step.
+struct Simplifier<'a> {
+    graph: &'a mut Graph,
+    report: SimplifyReport,
+    steps: Option<Vec<RewriteStep>>,
+}
+
+impl<'a> Simplifier<'a> {
+    fn new(graph: &'a mut Graph, record: bool) -> Self {
+        Simplifier { graph, report: SimplifyReport::default(), steps: record.then(Vec::new) }
+    }
+
+    /// Converts the diagram to graph-like form and simplifies it with `strategy`.
+    fn run(mut self, strategy: Strategy) -> Self {
+        self.apply(RewriteRule::GraphLike, vec![]);
+        match strategy {
+            Strategy::InteriorClifford => { self.interior_clifford(); }
+            Strategy::Clifford => { self.clifford(); }
+            Strategy::FullReduce => self.full_reduce(),
+        }
+        self
+    }
+
+    /// Applies `rule` at the matched `vertices`, which the caller has already checked.
+    fn apply(&mut self, rule: RewriteRule, vertices: Vec<VertexIndex>) {
+        match &mut self.steps {
+            Some(steps) => {
+                let step = RewriteStep::record(rule, vertices, self.graph).expect("match satisfies the rule");
+                steps.push(step);
+            }
+            None => rule.apply(self.graph, &vertices).expect("match satisfies the rule"),
+        }
+        self.report.count(rule);
+    }
+
+    /// Applies `rule` at each match returned by `find` until there are none, returning the
+    /// number of applications.
+    fn exhaust(&mut self, rule: RewriteRule, find: impl Fn(&Graph) -> Option<Vec<VertexIndex>>) -> usize {
+        let mut count = 0;
+        while let Some(vertices) = find(self.graph) {
+            self.apply(rule, vertices);
+            count += 1;
+        }
+        count
+    }
+
+    /// Applies spider fusion, identity removal and edge normalisation until none of them applies,
+    /// returning the number of rewrites made.
+    fn spider_simp(&mut self) -> usize {
+        let mut total = 0;
+        loop {
+            let count = self.exhaust(RewriteRule::Fusion, |graph| find_fusion(graph).map(|(v, w)| vec![v, w]))
+                + self.exhaust(RewriteRule::Identity, |graph| find_identity(graph).map(|v| vec![v]))
+                + self.exhaust(RewriteRule::SelfLoop, |graph| find_self_loop(graph).map(|v| vec![v]))
+                + self.exhaust(RewriteRule::Hopf, |graph| find_hopf(graph).map(|(v, w)| vec![v, w]));
+            if count == 0 {
+                return total;
+            }
+            total += count;
+        }
+    }
+
+    /// Removes interior Clifford spiders until none remain, returning the number of rewrites made.
+    fn interior_clifford(&mut self) -> usize {
+        let mut total = 0;
+        loop {
+            let count = self.spider_simp()
+                + self.exhaust(RewriteRule::LocalComplementation, |graph| {
+                    graph.find_lcomp_candidates().first().map(|&v| vec![v])
+                })
+                + self.exhaust(RewriteRule::Pivot, |graph| {
+                    graph.find_pivot_candidates().first().map(|&(u, v)| vec![u, v])
+                });
+            if count == 0 {
+                return total;
+            }
+            total += count;
+        }
+    }
+
+    /// Removes Clifford spiders, including Pauli spiders on boundaries, returning the number of
+    /// rewrites made.
+    fn clifford(&mut self) -> usize {
+        let mut total = 0;
+        loop {
+            total += self.interior_clifford();
+            let count = self.exhaust(RewriteRule::BoundaryPivot, |graph| {
+                graph.find_boundary_pivot_candidates().first().map(|&(u, v)| vec![u, v])
+            });
+            if count == 0 {
+                return total;
+            }
+            total += count;
+        }
+    }
+
+    /// Removes Clifford spiders, moving non-Clifford phases onto phase gadgets and fusing them.
+    fn full_reduce(&mut self) {
+        self.interior_clifford();
+        self.gadget_pivots();
+        loop {
+            self.clifford();
+            let fused = self.exhaust(RewriteRule::GadgetFusion, |graph| {
+                find_gadget_fusion(graph).map(|(v, w)| vec![v, w])
+            });
+            self.interior_clifford();
+            if fused + self.gadget_pivots() == 0 {
+                break;
+            }
+        }
+    }
+
+    /// Applies gadget pivoting until no candidates remain, returning the number applied.
+    fn gadget_pivots(&mut self) -> usize {
+        self.exhaust(RewriteRule::GadgetPivot, |graph| {
+            graph.find_gadget_pivot_candidates().first().map(|&(u, v)| vec![u, v])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, Gadget, GraphBuilder};
+    use crate::graph::phase::Phase;
+    use crate::rewrite::{GadgetFusion, RewriteError, RewriteRule, Simplify, Strategy};
+
+    #[test]
+    fn interior_clifford_simp_reduces_cx_pair() {
+        let mut graph = GraphBuilder::cx(0, 1).compose(&GraphBuilder::cx(0, 1)).unwrap();
+        let report = graph.interior_clifford_simp();
+        assert_eq!(report.identity, 2);
+        assert_eq!(report.total(), 2);
+        assert_eq!(graph.num_vertices(), 4);
+        assert_eq!(graph.num_edges(), 2);
+    }
+
+    #[test]
+    fn clifford_simp_leaves_only_boundary_spiders() {
+        let mut graph = GraphBuilder::cx(0, 1)
+            .compose(&GraphBuilder::z_plus(0)).unwrap()
+            .compose(&GraphBuilder::cz(0, 1)).unwrap()
+            .compose(&GraphBuilder::x_plus(1)).unwrap()
+            .compose(&GraphBuilder::cx(1, 0)).unwrap();
+        let report = graph.clifford_simp();
+        assert_eq!(report.lcomp, 2);
+        assert_eq!(graph.num_vertices(), 8);
+        let interior = graph.enumerate_vertices()
+            .filter(|&(index, _)| !graph.is_boundary(index))
+            .filter(|&(index, _)| graph.neighbours(index).all(|n| !graph.is_boundary(n)))
+            .count();
+        assert_eq!(interior, 0);
+    }
+
+    #[test]
+    fn full_reduce_fuses_gadgets() {
+        let mut graph = GraphBuilder::gadget("zz", Phase::new(0.125)).unwrap()
+            .compose(&GraphBuilder::gadget("zz", Phase::new(0.125)).unwrap()).unwrap();
+        let report = graph.full_reduce();
+        assert_eq!(report.gadget_fusion, 1);
+
+        let gadgets = graph.find_gadgets();
+        assert_eq!(gadgets.len(), 1);
+        assert_eq!(graph.vertex(gadgets[0].leaf).unwrap().phase(), Phase::new(0.25));
+        assert_eq!(graph.num_vertices(), 8);
+    }
+
+    #[test]
+    fn trace_replays_to_same_result() {
+        let circuit = GraphBuilder::gadget("zz", Phase::new(0.125)).unwrap()
+            .compose(&GraphBuilder::cx(0, 1)).unwrap()
+            .compose(&GraphBuilder::gadget("zz", Phase::new(0.125)).unwrap()).unwrap();
+        let mut simplified = circuit.clone();
+        let report = simplified.clone().full_reduce();
+
+        let derivation = simplified.trace(Strategy::FullReduce);
+        assert_eq!(derivation.report(), report);
+        assert_eq!(derivation.steps[0].rule, RewriteRule::GraphLike);
+        assert_eq!(derivation.steps.len(), report.total() + 1);
+        assert!(derivation.steps.iter().all(|step| !step.delta.is_empty()));
+
+        let replayed = derivation.replay().unwrap();
+        assert_eq!(replayed.num_vertices(), simplified.num_vertices());
+        assert_eq!(replayed.num_edges(), simplified.num_edges());
+        assert_eq!(derivation.graphs().unwrap().len(), derivation.steps.len() + 1);
+    }
+
+    #[test]
+    fn replay_rejects_tampered_steps() {
+        let mut graph = GraphBuilder::cx(0, 1).compose(&GraphBuilder::cx(0, 1)).unwrap();
+        let mut derivation = graph.trace(Strategy::InteriorClifford);
+        assert_eq!(derivation.steps.len(), 3);
+
+        assert!(derivation.replay().is_ok());
+
+        derivation.steps[2].delta.removed_vertices.clear();
+        assert!(matches!(derivation.replay(), Err(RewriteError::ReplayMismatch(2))));
+        derivation.steps[1].vertices.clear();
+        assert!(matches!(derivation.replay(), Err(RewriteError::ReplayMismatch(1))));
+    }
+}
@@ -0,0 +1,171 @@
+use crate::graph::{EdgeType, Graph, VertexIndex};
+use crate::rewrite::{
+    Derivation, GadgetFusion, GraphDelta, GraphLike, Hopf, IdentityRemoval, LocalComplementation, Pivot,
+    RewriteError, RewriteRule, RewriteStep, SimplifyReport, SpiderFusion,
+};
+use petgraph::prelude::EdgeRef;
+use std::collections::HashMap;
+
+impl RewriteRule {
+    /// Applies the rule to `graph` at the matched `vertices`.
+    pub fn apply(self, graph: &mut Graph, vertices: &[VertexIndex]) -> Result<(), RewriteError> {
+        match (self, vertices) {
+            (RewriteRule::GraphLike, []) => graph.to_graph_like(),
+            (RewriteRule::Fusion, &[v, w]) => graph.fuse(v, w)?,
+            (RewriteRule::Identity, &[v]) => graph.remove_identity(v)?,
+            (RewriteRule::Hopf, &[v, w]) => { graph.hopf(v, w)?; }
+            (RewriteRule::SelfLoop, &[v]) => { graph.remove_self_loop(v)?; }
+            (RewriteRule::LocalComplementation, &[v]) => graph.lcomp(v)?,
+            (RewriteRule::Pivot, &[u, v]) => graph.pivot(u, v)?,
+            (RewriteRule::BoundaryPivot, &[u, v]) => graph.pivot_boundary(u, v)?,
+            (RewriteRule::GadgetPivot, &[u, v]) => graph.pivot_gadget(u, v)?,
+            (RewriteRule::GadgetFusion, &[v, w]) => graph.fuse_gadgets(v, w)?,
+            _ => return Err(RewriteError::MatchArity(self, self.arity())),
+        }
+        Ok(())
+    }
+
+    /// Returns the number of vertices the rule is applied to.
+    pub fn arity(self) -> usize {
+        match self {
+            RewriteRule::GraphLike => 0,
+            RewriteRule::Identity | RewriteRule::SelfLoop | RewriteRule::LocalComplementation => 1,
+            _ => 2,
+        }
+    }
+}
+
+impl RewriteStep {
+    /// Applies the rule of the step to `graph`, recording the change it makes.
+    pub(super) fn record(rule: RewriteRule, vertices: Vec<VertexIndex>, graph: &mut Graph) -> Result<Self, RewriteError> {
+        let before = graph.clone();
+        rule.apply(graph, &vertices)?;
+        Ok(RewriteStep { rule, vertices, delta: GraphDelta::between(&before, graph) })
+    }
+}
+
+impl GraphDelta {
+    /// Returns the change that turns `before` into `after`.
+    ///
+    /// A vertex whose type changed is treated as removed and added again.
+    ///
+    /// Runs in **O(n + m)** time, where *n* is the number of vertices and *m* the number of edges.
+    pub fn between(before: &Graph, after: &Graph) -> Self {
+        let mut delta = GraphDelta::default();
+        for (index, vertex) in before.enumerate_vertices() {
+            match after.vertex(index) {
+                Some(other) if other.vertex_type() == vertex.vertex_type() => {
+                    if other.phase() != vertex.phase() {
+                        delta.phases.push((index, vertex.phase(), other.phase()));
+                    }
+                }
+                _ => delta.removed_vertices.push(index),
+            }
+        }
+        for (index, vertex) in after.enumerate_vertices() {
+            let kept = before.vertex(index).is_some_and(|other| other.vertex_type() == vertex.vertex_type());
+            if !kept {
+                delta.added_vertices.push(index);
+            }
+        }
+
+        let mut counts: HashMap<(VertexIndex, VertexIndex, EdgeType), isize> = HashMap::new();
+        for edge in before.enumerate_edges() {
+            *counts.entry(edge_key(edge.source(), edge.target(), *edge.weight())).or_default() -= 1;
+        }
+        for edge in after.enumerate_edges() {
+            *counts.entry(edge_key(edge.source(), edge.target(), *edge.weight())).or_default() += 1;
+        }
+        for (edge, count) in counts {
+            let edges = if count > 0 { &mut delta.added_edges } else { &mut delta.removed_edges };
+            edges.extend(std::iter::repeat_n(edge, count.unsigned_abs()));
+        }
+        delta.added_edges.sort();
+        delta.removed_edges.sort();
+        if before.scalar() != after.scalar() {
+            delta.scalar = Some((before.scalar().clone(), after.scalar().clone()));
+        }
+        delta
+    }
+
+    /// Returns true if the delta makes no change.
+    pub fn is_empty(&self) -> bool {
+        self.added_vertices.is_empty()
+            && self.removed_vertices.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.phases.is_empty()
+            && self.scalar.is_none()
+    }
+}
+
+impl Derivation {
+    /// Applies every step to a clone of the initial diagram, returning the final diagram.
+    ///
+    /// Fails with [`RewriteError::ReplayMismatch`] if a step does not make its recorded change.
+    pub fn replay(&self) -> Result<Graph, RewriteError> {
+        let mut graph = self.initial.clone();
+        for (number, step) in self.steps.iter().enumerate() {
+            replay_step(&mut graph, number, step)?;
+        }
+        Ok(graph)
+    }
+
+    /// Returns the initial diagram followed by the diagram after each step.
+    ///
+    /// Fails with [`RewriteError::ReplayMismatch`] if a step does not make its recorded change.
+    pub fn graphs(&self) -> Result<Vec<Graph>, RewriteError> {
+        let mut graph = self.initial.clone();
+        let mut graphs = Vec::with_capacity(self.steps.len() + 1);
+        graphs.push(graph.clone());
+        for (number, step) in self.steps.iter().enumerate() {
+            replay_step(&mut graph, number, step)?;
+            graphs.push(graph.clone());
+        }
+        Ok(graphs)
+    }
+
+    /// Returns the number of times each rule was applied.
+    ///
+    /// Conversion to graph-like form is not counted.
+    pub fn report(&self) -> SimplifyReport {
+        let mut report = SimplifyReport::default();
+        for step in &self.steps {
+            report.count(step.rule);
+        }
+        report
+    }
+}
+
+impl SimplifyReport {
+    /// Counts one application of `rule`.
+    pub(super) fn count(&mut self, rule: RewriteRule) {
+        match rule {
+            RewriteRule::GraphLike => {}
+            RewriteRule::Fusion => self.fusion += 1,
+            RewriteRule::Identity => self.identity += 1,
+            RewriteRule::Hopf => self.hopf += 1,
+            RewriteRule::SelfLoop => self.self_loops += 1,
+            RewriteRule::LocalComplementation => self.lcomp += 1,
+            RewriteRule::Pivot => self.pivot += 1,
+            RewriteRule::BoundaryPivot => self.boundary_pivot += 1,
+            RewriteRule::GadgetPivot => self.gadget_pivot += 1,
+            RewriteRule::GadgetFusion => self.gadget_fusion += 1,
+        }
+    }
+}
+
+/// Applies `step` to `graph`, checking that it makes the recorded change.
+fn replay_step(graph: &mut Graph, number: usize, step: &RewriteStep) -> Result<(), RewriteError> {
+    let replayed = RewriteStep::record(step.rule, step.vertices.clone(), graph)
+        .map_err(|_| RewriteError::ReplayMismatch(number))?;
+    if replayed.delta != step.delta {
+        return Err(RewriteError::ReplayMismatch(number));
+    }
+    Ok(())
+}
+
+/// Returns the edge between `a` and `b` with its endpoints in ascending order.
+fn edge_key(a: VertexIndex, b: VertexIndex, edge_type: EdgeType) -> (VertexIndex, VertexIndex, EdgeType) {
+    (a.min(b), a.max(b), edge_type)
+}
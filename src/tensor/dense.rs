@@ -0,0 +1,132 @@
+use num_complex::Complex64;
+
+/// A dense tensor whose axes all have dimension 2
+///
+/// Each axis carries a label, and tensors are contracted along the axes whose labels they
+/// share. Entries are stored with the first axis as the most significant bit of the index.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Tensor {
+    labels: Vec<usize>,
+    data: Vec<Complex64>,
+}
+
+impl Tensor {
+    /// Creates a tensor with the given axis labels, computing each entry from the bits of its
+    /// index, one per axis.
+    pub(crate) fn from_fn(labels: Vec<usize>, entry: impl Fn(&[bool]) -> Complex64) -> Self {
+        let rank = labels.len();
+        let mut bits = vec![false; rank];
+        let data = (0..1usize << rank)
+            .map(|index| {
+                for (axis, bit) in bits.iter_mut().enumerate() {
+                    *bit = index >> (rank - 1 - axis) & 1 == 1;
+                }
+                entry(&bits)
+            })
+            .collect();
+        Tensor { labels, data }
+    }
+
+    /// Returns the rank-0 tensor holding `value`.
+    pub(crate) fn scalar(value: Complex64) -> Self {
+        Tensor { labels: Vec::new(), data: vec![value] }
+    }
+
+    pub(crate) fn labels(&self) -> &[usize] {
+        &self.labels
+    }
+
+    /// Returns the entries, with the first axis as the most significant bit of the index.
+    pub(crate) fn data(&self) -> &[Complex64] {
+        &self.data
+    }
+
+    /// Returns the tensor with its axes reordered to follow `labels`.
+    ///
+    /// Panics if `labels` is not a permutation of the labels of the tensor.
+    ///
+    /// Runs in **O(r·2^r)** time, where *r* is the rank of the tensor.
+    pub(crate) fn permute(&self, labels: &[usize]) -> Tensor {
+        let rank = self.labels.len();
+        assert_eq!(labels.len(), rank, "permutation has the wrong rank");
+        let shifts: Vec<usize> = self.labels.iter()
+            .map(|label| {
+                let axis = labels.iter().position(|other| other == label).expect("label is permuted");
+                rank - 1 - axis
+            })
+            .collect();
+
+        let mut data = vec![Complex64::ZERO; self.data.len()];
+        for (index, &entry) in self.data.iter().enumerate() {
+            let permuted = shifts.iter()
+                .enumerate()
+                .fold(0, |permuted, (axis, shift)| permuted | (index >> (rank - 1 - axis) & 1) << shift);
+            data[permuted] = entry;
+        }
+        Tensor { labels: labels.to_vec(), data }
+    }
+
+    /// Contracts `self` with `other` along every label they share.
+    ///
+    /// The axes of the result are the remaining axes of `self` followed by those of `other`.
+    ///
+    /// Runs in **O(2^(a + b + s))** time, where *a* and *b* are the numbers of axes only in
+    /// `self` and `other`, and *s* the number of shared axes.
+    pub(crate) fn contract(&self, other: &Tensor) -> Tensor {
+        let shared: Vec<usize> = self.labels.iter().copied().filter(|label| other.labels.contains(label)).collect();
+        let left: Vec<usize> = self.labels.iter().copied().filter(|label| !shared.contains(label)).collect();
+        let right: Vec<usize> = other.labels.iter().copied().filter(|label| !shared.contains(label)).collect();
+
+        let a = self.permute(&[left.as_slice(), &shared].concat());
+        let b = other.permute(&[shared.as_slice(), &right].concat());
+        let (rows, inner, cols) = (1usize << left.len(), 1usize << shared.len(), 1usize << right.len());
+
+        let mut data = vec![Complex64::ZERO; rows * cols];
+        for row in 0..rows {
+            for k in 0..inner {
+                let entry = a.data[row * inner + k];
+                if entry == Complex64::ZERO {
+                    continue;
+                }
+                for col in 0..cols {
+                    data[row * cols + col] += entry * b.data[k * cols + col];
+                }
+            }
+        }
+        Tensor { labels: [left, right].concat(), data }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Tensor;
+    use num_complex::Complex64;
+
+    fn real(value: f64) -> Complex64 {
+        Complex64::new(value, 0.0)
+    }
+
+    #[test]
+    fn permute_swaps_axes() {
+        let tensor = Tensor::from_fn(vec![0, 1], |bits| real(if bits[0] && !bits[1] { 1.0 } else { 0.0 }));
+        let swapped = tensor.permute(&[1, 0]);
+        assert_eq!(swapped.labels, [1, 0]);
+        assert_eq!(tensor.data(), [real(0.0), real(0.0), real(1.0), real(0.0)]);
+        assert_eq!(swapped.data(), [real(0.0), real(1.0), real(0.0), real(0.0)]);
+    }
+
+    #[test]
+    fn contract_multiplies_matrices() {
+        // [[1, 2], [3, 4]] · [[0, 1], [1, 0]] = [[2, 1], [4, 3]]
+        let a = Tensor::from_fn(vec![0, 1], |bits| real(1.0 + 2.0 * bits[0] as u8 as f64 + bits[1] as u8 as f64));
+        let b = Tensor::from_fn(vec![1, 2], |bits| real((bits[0] != bits[1]) as u8 as f64));
+        let product = a.contract(&b);
+        assert_eq!(product.labels, [0, 2]);
+        assert_eq!(product.data(), [real(2.0), real(1.0), real(4.0), real(3.0)]);
+
+        let trace = a.contract(&Tensor::from_fn(vec![0, 1], |bits| real((bits[0] == bits[1]) as u8 as f64)));
+        assert_eq!(trace.data(), [real(5.0)]);
+        assert_eq!(Tensor::scalar(real(2.0)).contract(&trace).data(), [real(10.0)]);
+    }
+}
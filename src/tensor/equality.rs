@@ -0,0 +1,118 @@
+use crate::graph::Graph;
+use crate::tensor::evaluate::Network;
+use crate::tensor::random::Rng;
+use crate::tensor::{Evaluate, Matrix, SemanticEquality};
+use std::collections::HashMap;
+
+impl SemanticEquality for Graph {
+    /// Returns true if both diagrams evaluate to the same linear map up to a non-zero scalar.
+    ///
+    /// Diagrams with different numbers of inputs or outputs are never equal. Inputs and outputs
+    /// are matched in ascending order of qubit. Entries are compared with `tolerance` after
+    /// both maps are divided by their entry at the position of the largest entry of `self`, as
+    /// in [`Matrix::is_proportional_to`].
+    ///
+    /// Runs in time exponential in the number of qubits, see [`Evaluate::to_matrix`].
+    fn equals_semantically(&self, other: &Graph, tolerance: f64) -> bool {
+        same_shape(self, other) && self.to_matrix().is_proportional_to(&other.to_matrix(), tolerance)
+    }
+
+    /// Returns true if both diagrams agree up to a non-zero scalar on `samples` random product
+    /// states drawn from `seed`.
+    ///
+    /// Each sample plugs the same random single-qubit vector into the matching input or output
+    /// of both diagrams and contracts them to a single amplitude, so the full matrices are never
+    /// built. Diagrams that are proportional always pass, and diagrams that are not fail with
+    /// high probability for a handful of samples.
+    ///
+    /// Runs in time exponential only in the number of open edges during contraction.
+    fn equals_semantically_randomized(&self, other: &Graph, samples: usize, seed: u64, tolerance: f64) -> bool {
+        if !same_shape(self, other) {
+            return false;
+        }
+        let (left, right) = (Network::new(self), Network::new(other));
+        let mut rng = Rng::new(seed);
+        let mut amplitudes = (Vec::with_capacity(samples), Vec::with_capacity(samples));
+
+        for _ in 0..samples {
+            let vectors: Vec<_> = (0..left.outputs.len() + left.inputs.len()).map(|_| rng.qubit()).collect();
+            let plug = |network: &Network| -> HashMap<_, _> {
+                network.outputs.iter().chain(&network.inputs).copied().zip(vectors.iter().copied()).collect()
+            };
+            amplitudes.0.push(left.amplitude(&plug(&left)));
+            amplitudes.1.push(right.amplitude(&plug(&right)));
+        }
+
+        let (left, right) = (Matrix::new(1, samples, amplitudes.0), Matrix::new(1, samples, amplitudes.1));
+        left.is_proportional_to(&right, tolerance)
+    }
+}
+
+/// Returns true if both diagrams have the same numbers of inputs and outputs.
+fn same_shape(graph: &Graph, other: &Graph) -> bool {
+    graph.num_inputs() == other.num_inputs() && graph.num_outputs() == other.num_outputs()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, Gadget, GraphBuilder, Pauli};
+    use crate::graph::phase::Phase;
+    use crate::graph::Graph;
+    use crate::rewrite::Simplify;
+    use crate::tensor::SemanticEquality;
+
+    const TOLERANCE: f64 = 1e-9;
+
+    fn wires(qubits: usize) -> Graph {
+        let mut graph = Graph::new(qubits);
+        graph.add_wires_excluding(0..qubits, []).unwrap();
+        graph
+    }
+
+    #[test]
+    fn cx_is_self_inverse() {
+        let cx = GraphBuilder::cx(0, 1).compose(&GraphBuilder::cx(0, 1)).unwrap();
+        assert!(cx.equals_semantically(&wires(2), TOLERANCE));
+        assert!(cx.equals_semantically_randomized(&wires(2), 8, 1, TOLERANCE));
+        assert!(!GraphBuilder::cx(0, 1).equals_semantically(&wires(2), TOLERANCE));
+        assert!(!GraphBuilder::cx(0, 1).equals_semantically_randomized(&wires(2), 8, 1, TOLERANCE));
+    }
+
+    #[test]
+    fn distinguishes_gadget_phases() {
+        let gadget = GraphBuilder::gadget("zxy", Phase::new(0.25)).unwrap();
+        let other = GraphBuilder::gadget("zxy", Phase::new(0.125)).unwrap();
+        assert!(gadget.equals_semantically(&gadget.adjoint().adjoint(), TOLERANCE));
+        assert!(!gadget.equals_semantically(&other, TOLERANCE));
+        assert!(!gadget.equals_semantically_randomized(&other, 8, 2, TOLERANCE));
+        assert!(!gadget.equals_semantically_randomized(&GraphBuilder::gadget("zyx", Phase::new(0.25)).unwrap(), 8, 2, TOLERANCE));
+    }
+
+    #[test]
+    fn requires_matching_boundaries() {
+        assert!(!wires(2).equals_semantically(&wires(3), TOLERANCE));
+        assert!(!wires(2).equals_semantically_randomized(&wires(3), 8, 3, TOLERANCE));
+        assert!(!GraphBuilder::pauli_y(0).equals_semantically(&wires(1), TOLERANCE));
+    }
+
+    #[test]
+    fn full_reduce_preserves_semantics_on_random_states() {
+        let circuits = [
+            GraphBuilder::gadget("zz", Phase::new(0.125)).unwrap()
+                .compose(&GraphBuilder::cx(0, 1)).unwrap()
+                .compose(&GraphBuilder::gadget("zz", Phase::new(0.125)).unwrap()).unwrap(),
+            GraphBuilder::gadget("xyzy", Phase::new(0.75)).unwrap()
+                .compose(&GraphBuilder::cz(1, 3)).unwrap()
+                .compose(&GraphBuilder::gadget("yzix", Phase::new(0.25)).unwrap()).unwrap()
+                .compose(&GraphBuilder::cx(2, 0)).unwrap()
+                .compose(&GraphBuilder::x_plus(3)).unwrap(),
+        ];
+        for (seed, circuit) in circuits.iter().enumerate() {
+            let mut simplified = circuit.clone();
+            simplified.full_reduce();
+            assert!(circuit.equals_semantically(&simplified, TOLERANCE));
+            assert!(circuit.equals_semantically_randomized(&simplified, 8, seed as u64, TOLERANCE));
+        }
+    }
+}
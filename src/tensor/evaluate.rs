@@ -0,0 +1,388 @@
+use crate::graph::{EdgeType, Graph, Vertex, VertexIndex, VertexType};
+use crate::tensor::contract::contract_greedy;
+use crate::tensor::dense::Tensor;
+use crate::tensor::{Evaluate, Matrix};
+use num_complex::Complex64;
+use petgraph::prelude::EdgeRef;
+use std::collections::HashMap;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+impl Evaluate for Graph {
+    /// Returns the linear map of the diagram as a 2^outputs × 2^inputs matrix
+    ///
+    /// Rows are indexed by the outputs and columns by the inputs, each in ascending order of
+    /// qubit with the lowest qubit as the most significant bit. Z and X spiders are contracted
+    /// with their usual unnormalised tensors, and Y spiders with their legs oriented as given by
+    /// [`VertexType::Y`]. Hadamard vertices act as H-boxes scaled by 1/√2, so that a Hadamard
+    /// vertex with two edges is a Hadamard gate. Boundaries that are not inputs or outputs act as
+    /// phase-free Z spiders. The result is multiplied by the scalar of the diagram.
+    ///
+    /// Panics if a phase is symbolic.
+    ///
+    /// Runs in time exponential in the number of qubits, and in the number of open bonds at any
+    /// point of the contraction, see [`Evaluate::amplitude`].
+    fn to_matrix(&self) -> Matrix {
+        Network::new(self).to_matrix()
+    }
+
+    /// Returns the amplitude ⟨`outputs`|D|`inputs`⟩ of the diagram D between two basis states
+    ///
+    /// The bits of each basis state are given in ascending order of qubit. The diagram is
+    /// contracted as a tensor network with one tensor per vertex and one bond per edge, along an
+    /// order chosen greedily so that the intermediate tensors stay small, without building the
+    /// full matrix.
+    ///
+    /// Panics if the number of bits differs from the number of outputs or inputs, or if a phase
+    /// is symbolic.
+    ///
+    /// Runs in time exponential in the number of open bonds at any point of the contraction,
+    /// which stays small for diagrams of low treewidth however many qubits they act on.
+    fn amplitude(&self, outputs: &[bool], inputs: &[bool]) -> Complex64 {
+        let network = Network::new(self);
+        assert_eq!(outputs.len(), network.outputs.len(), "one bit is needed per output");
+        assert_eq!(inputs.len(), network.inputs.len(), "one bit is needed per input");
+
+        let basis = |bit: bool| if bit { [Complex64::ZERO, Complex64::ONE] } else { [Complex64::ONE, Complex64::ZERO] };
+        let vectors = network.outputs.iter().zip(outputs)
+            .chain(network.inputs.iter().zip(inputs))
+            .map(|(&label, &bit)| (label, basis(bit)))
+            .collect();
+        network.amplitude(&vectors)
+    }
+}
+
+/// The tensors of a diagram, and the labels of its open axes
+pub(crate) struct Network {
+    /// One tensor per vertex, sharing a label for every edge between them, and the scalar of the
+    /// diagram
+    tensors: Vec<Tensor>,
+    /// Labels of the outputs and inputs, in ascending order of qubit
+    pub(crate) outputs: Vec<usize>,
+    pub(crate) inputs: Vec<usize>,
+}
+
+impl Network {
+    /// Builds the tensor of every vertex of `graph`.
+    ///
+    /// Each edge is a bond labelled by its index. The stored target of a `Hadamard` edge, and of
+    /// a self-loop, first takes a label of its own, which the tensor of the edge then joins to
+    /// the bond before the vertex enters the network.
+    pub(crate) fn new(graph: &Graph) -> Self {
+        let bound = graph.enumerate_edges().map(|edge| edge.id().index() + 1).max().unwrap_or(0);
+        let open = |qubit: usize, output: bool| 2 * bound + 2 * qubit + output as usize;
+
+        let mut inputs: Vec<usize> = graph.input_qubits().copied().collect();
+        let mut outputs: Vec<usize> = graph.output_qubits().copied().collect();
+        inputs.sort();
+        outputs.sort();
+        let mut open_labels: HashMap<VertexIndex, usize> = HashMap::new();
+        for &qubit in &inputs {
+            open_labels.insert(*graph.input_index(qubit).expect("input exists"), open(qubit, false));
+        }
+        for &qubit in &outputs {
+            open_labels.insert(*graph.output_index(qubit).expect("output exists"), open(qubit, true));
+        }
+
+        let mut legs: HashMap<VertexIndex, Vec<(usize, bool)>> = HashMap::new();
+        let mut joins: HashMap<VertexIndex, Vec<Tensor>> = HashMap::new();
+        for edge in graph.enumerate_edges() {
+            let bond = edge.id().index();
+            legs.entry(edge.source()).or_default().push((bond, true));
+            if *edge.weight() == EdgeType::Simple && edge.source() != edge.target() {
+                legs.entry(edge.target()).or_default().push((bond, false));
+            } else {
+                legs.entry(edge.target()).or_default().push((bound + bond, false));
+                joins.entry(edge.target()).or_default().push(edge_tensor(*edge.weight(), bound + bond, bond));
+            }
+        }
+
+        let scalar = Tensor::scalar(graph.scalar().to_complex());
+        let tensors = graph.enumerate_vertices()
+            .map(|(index, vertex)| {
+                let legs = legs.get(&index).map_or(&[][..], Vec::as_slice);
+                let tensor = vertex_tensor(vertex, legs, open_labels.get(&index).copied());
+                joins.remove(&index).unwrap_or_default().iter().fold(tensor, |tensor, join| tensor.contract(join))
+            })
+            .chain([scalar])
+            .collect();
+
+        Network {
+            tensors,
+            outputs: outputs.into_iter().map(|qubit| open(qubit, true)).collect(),
+            inputs: inputs.into_iter().map(|qubit| open(qubit, false)).collect(),
+        }
+    }
+
+    /// Contracts the network with a vector on every open label, returning a single amplitude.
+    ///
+    /// Panics if `vectors` does not cover every open label.
+    pub(crate) fn amplitude(&self, vectors: &HashMap<usize, [Complex64; 2]>) -> Complex64 {
+        let caps = self.outputs.iter().chain(&self.inputs).map(|&label| {
+            let vector = vectors.get(&label).expect("every open label needs a vector");
+            Tensor::from_fn(vec![label], |bits| vector[bits[0] as usize])
+        });
+        contract_greedy(self.tensors.iter().cloned().chain(caps).collect()).data()[0]
+    }
+
+    /// Contracts the network into a 2^outputs × 2^inputs matrix.
+    pub(crate) fn to_matrix(&self) -> Matrix {
+        let labels = [self.outputs.as_slice(), &self.inputs].concat();
+        let tensor = contract_greedy(self.tensors.clone()).permute(&labels);
+        Matrix::new(1 << self.outputs.len(), 1 << self.inputs.len(), tensor.data().to_vec())
+    }
+}
+
+/// Returns the tensor of `vertex`, given the label of each of its legs and whether the vertex
+/// is the stored source of that leg, and the label of its open wire if it is an input or output.
+fn vertex_tensor(vertex: &Vertex, legs: &[(usize, bool)], open: Option<usize>) -> Tensor {
+    let phase = Complex64::from_polar(1.0, vertex.phase().to_f64().expect("phase is constant") * PI);
+    let labels: Vec<usize> = legs.iter().map(|&(label, _)| label).chain(open).collect();
+    let arity = labels.len();
+
+    match vertex.vertex_type() {
+        VertexType::Z | VertexType::B => Tensor::from_fn(labels, |bits| {
+            if bits.iter().all(|&bit| !bit) {
+                Complex64::ONE
+            } else if bits.iter().all(|&bit| bit) {
+                phase
+            } else {
+                Complex64::ZERO
+            }
+        }),
+        VertexType::X => {
+            let norm = FRAC_1_SQRT_2.powi(arity as i32);
+            Tensor::from_fn(labels, |bits| {
+                let parity = bits.iter().filter(|&&bit| bit).count() % 2;
+                (Complex64::ONE + if parity == 0 { phase } else { -phase }) * norm
+            })
+        }
+        VertexType::Y => {
+            // Source legs are kets of the Y basis, and target legs are bras
+            let plus = |bit: bool, source: bool| match (bit, source) {
+                (false, _) => Complex64::new(FRAC_1_SQRT_2, 0.0),
+                (true, true) => Complex64::new(0.0, FRAC_1_SQRT_2),
+                (true, false) => Complex64::new(0.0, -FRAC_1_SQRT_2),
+            };
+            let orientations: Vec<bool> = legs.iter().map(|&(_, source)| source).chain(open.map(|_| true)).collect();
+            Tensor::from_fn(labels, |bits| {
+                let (mut first, mut second) = (Complex64::ONE, phase);
+                for (&bit, &source) in bits.iter().zip(&orientations) {
+                    first *= plus(bit, source);
+                    second *= plus(bit, !source);
+                }
+                first + second
+            })
+        }
+        VertexType::H => Tensor::from_fn(labels, |bits| {
+            let sign = if !bits.is_empty() && bits.iter().all(|&bit| bit) { -1.0 } else { 1.0 };
+            Complex64::new(sign * FRAC_1_SQRT_2, 0.0)
+        }),
+    }
+}
+
+/// Returns the tensor of an edge of type `edge_type` joining the labels `a` and `b`.
+fn edge_tensor(edge_type: EdgeType, a: usize, b: usize) -> Tensor {
+    Tensor::from_fn(vec![a, b], |bits| match edge_type {
+        EdgeType::Simple => Complex64::new((bits[0] == bits[1]) as u8 as f64, 0.0),
+        EdgeType::Hadamard => {
+            let sign = if bits[0] && bits[1] { -1.0 } else { 1.0 };
+            Complex64::new(sign * FRAC_1_SQRT_2, 0.0)
+        }
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::builders::{Clifford, Gadget, GraphBuilder, Pauli};
+    use crate::graph::phase::Phase;
+    use crate::graph::Graph;
+    use crate::rewrite::{Simplify, Strategy};
+    use crate::tensor::{Evaluate, Matrix};
+    use num_complex::Complex64;
+
+    const TOLERANCE: f64 = 1e-9;
+
+    fn matrix(rows: usize, cols: usize, entries: &[(f64, f64)]) -> Matrix {
+        Matrix::new(rows, cols, entries.iter().map(|&(re, im)| Complex64::new(re, im)).collect())
+    }
+
+    fn pauli(name: char) -> Matrix {
+        match name {
+            'i' => Matrix::identity(2),
+            'x' => matrix(2, 2, &[(0.0, 0.0), (1.0, 0.0), (1.0, 0.0), (0.0, 0.0)]),
+            'y' => matrix(2, 2, &[(0.0, 0.0), (0.0, -1.0), (0.0, 1.0), (0.0, 0.0)]),
+            'z' => matrix(2, 2, &[(1.0, 0.0), (0.0, 0.0), (0.0, 0.0), (-1.0, 0.0)]),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns `exp(-i phase/2 P)` for the Pauli string `P`, with `phase` in multiples of π.
+    fn pauli_exponential(pauli_string: &str, phase: f64) -> Matrix {
+        let string = pauli_string.chars().fold(Matrix::identity(1), |string, name| string.kron(&pauli(name)));
+        let angle = phase * std::f64::consts::PI / 2.0;
+        let size = string.rows();
+        let cos = Matrix::identity(size).scale(Complex64::new(angle.cos(), 0.0));
+        let sin = string.scale(Complex64::new(0.0, -angle.sin()));
+        Matrix::new(size, size, cos.data().iter().zip(sin.data()).map(|(a, b)| a + b).collect())
+    }
+
+    fn circuit() -> Graph {
+        GraphBuilder::gadget("zxy", Phase::new(0.25)).unwrap()
+            .compose(&GraphBuilder::cx(0, 2)).unwrap()
+            .compose(&GraphBuilder::gadget("yzx", Phase::new(0.125)).unwrap()).unwrap()
+            .compose(&GraphBuilder::cz(1, 2)).unwrap()
+            .compose(&GraphBuilder::z_plus(0)).unwrap()
+            .compose(&GraphBuilder::gadget("xxz", Phase::new(0.5)).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn paulis() {
+        for (graph, name) in [
+            (GraphBuilder::pauli_x(0), 'x'),
+            (GraphBuilder::pauli_y(0), 'y'),
+            (GraphBuilder::pauli_z(0), 'z'),
+        ] {
+            assert!(graph.to_matrix().is_proportional_to(&pauli(name), TOLERANCE));
+        }
+        let x = GraphBuilder::pauli_x(1).to_matrix();
+        assert!(x.is_proportional_to(&Matrix::identity(2).kron(&pauli('x')), TOLERANCE));
+    }
+
+    #[test]
+    fn cliffords() {
+        let (one, zero, i) = ((1.0, 0.0), (0.0, 0.0), (0.0, 1.0));
+        let cx = matrix(4, 4, &[
+            one, zero, zero, zero,
+            zero, one, zero, zero,
+            zero, zero, zero, one,
+            zero, zero, one, zero,
+        ]);
+        assert!(GraphBuilder::cx(0, 1).to_matrix().is_proportional_to(&cx, TOLERANCE));
+        assert!(!GraphBuilder::cx(1, 0).to_matrix().is_proportional_to(&cx, TOLERANCE));
+
+        let cz = matrix(4, 4, &[
+            one, zero, zero, zero,
+            zero, one, zero, zero,
+            zero, zero, one, zero,
+            zero, zero, zero, (-1.0, 0.0),
+        ]);
+        assert!(GraphBuilder::cz(0, 1).to_matrix().is_proportional_to(&cz, TOLERANCE));
+
+        let s = matrix(2, 2, &[one, zero, zero, i]);
+        assert!(GraphBuilder::z_plus(0).to_matrix().is_proportional_to(&s, TOLERANCE));
+        assert!(GraphBuilder::z_minus(0).to_matrix().is_proportional_to(&s.adjoint(), TOLERANCE));
+        assert!(GraphBuilder::x_plus(0).to_matrix().is_proportional_to(&pauli_exponential("x", 0.5), TOLERANCE));
+        assert!(GraphBuilder::y_plus(0).to_matrix().is_proportional_to(&pauli_exponential("y", 0.5), TOLERANCE));
+    }
+
+    #[test]
+    fn gadgets_are_pauli_exponentials() {
+        for (pauli_string, phase) in [("zxy", 0.25), ("zix", 0.125), ("yy", 0.5), ("xyz", 1.75)] {
+            let graph = GraphBuilder::gadget(pauli_string, Phase::new(phase)).unwrap();
+            let expected = pauli_exponential(pauli_string, phase);
+            assert!(graph.to_matrix().is_proportional_to(&expected, TOLERANCE), "{pauli_string}");
+        }
+    }
+
+    #[test]
+    fn adjoint_transpose_and_conjugate() {
+        let graph = circuit();
+        let matrix = graph.to_matrix();
+        let transpose = Matrix::new(matrix.cols(), matrix.rows(), matrix.adjoint().data().iter().map(|entry| entry.conj()).collect());
+        assert!(graph.adjoint().to_matrix().is_proportional_to(&matrix.adjoint(), TOLERANCE));
+        assert!(graph.transpose().to_matrix().is_proportional_to(&transpose, TOLERANCE));
+        assert!(graph.conjugate().to_matrix().is_proportional_to(&transpose.adjoint(), TOLERANCE));
+        assert!((&graph.to_matrix() * &graph.adjoint().to_matrix()).is_proportional_to(&Matrix::identity(8), TOLERANCE));
+    }
+
+    #[test]
+    fn composition_and_tensor_products() {
+        let left = GraphBuilder::gadget("zy", Phase::new(0.25)).unwrap();
+        let right = GraphBuilder::gadget("yx", Phase::new(0.75)).unwrap();
+        let composed = left.compose(&right).unwrap().to_matrix();
+        assert!(composed.is_proportional_to(&(&right.to_matrix() * &left.to_matrix()), TOLERANCE));
+        let tensor = left.tensor(&right).unwrap().to_matrix();
+        assert!(tensor.is_proportional_to(&left.to_matrix().kron(&right.to_matrix()), TOLERANCE));
+    }
+
+    #[test]
+    fn amplitudes_match_matrix_entries() {
+        let graph = circuit();
+        let matrix = graph.to_matrix();
+        let bits = |index: usize| [index >> 2 & 1 == 1, index >> 1 & 1 == 1, index & 1 == 1];
+        for row in 0..8 {
+            for col in 0..8 {
+                assert!((graph.amplitude(&bits(row), &bits(col)) - matrix.get(row, col)).norm() < TOLERANCE);
+            }
+        }
+    }
+
+    #[test]
+    fn amplitudes_of_wide_circuits() {
+        // A ladder of CX gates maps |1 0 … 0⟩ to |1 1 … 1⟩
+        let qubits = 40;
+        let graph = (1..qubits)
+            .map(|target| GraphBuilder::cx(target - 1, target))
+            .reduce(|graph, cx| graph.compose(&cx).unwrap())
+            .unwrap();
+        let mut input = vec![false; qubits];
+        input[0] = true;
+        let scale = graph.amplitude(&vec![false; qubits], &vec![false; qubits]);
+        assert!(scale.norm() > TOLERANCE);
+        assert!((graph.amplitude(&vec![true; qubits], &input) - scale).norm() < TOLERANCE * scale.norm());
+        assert!(graph.amplitude(&vec![false; qubits], &input).norm() < TOLERANCE * scale.norm());
+    }
+
+    #[test]
+    #[should_panic(expected = "one bit is needed per input")]
+    fn amplitude_requires_every_input() {
+        GraphBuilder::cx(0, 1).amplitude(&[false, false], &[false]);
+    }
+
+    #[test]
+    fn rewrites_preserve_semantics_exactly() {
+        let circuits = [
+            circuit(),
+            GraphBuilder::cx(0, 1)
+                .compose(&GraphBuilder::pauli_z(0)).unwrap()
+                .compose(&GraphBuilder::cz(0, 1)).unwrap()
+                .compose(&GraphBuilder::x_plus(1)).unwrap()
+                .compose(&GraphBuilder::pauli_x(1)).unwrap()
+                .compose(&GraphBuilder::cx(1, 0)).unwrap(),
+            GraphBuilder::gadget("zzy", Phase::new(0.125)).unwrap()
+                .compose(&GraphBuilder::cx(0, 1)).unwrap()
+                .compose(&GraphBuilder::pauli_x(2)).unwrap()
+                .compose(&GraphBuilder::gadget("zzy", Phase::new(0.375)).unwrap()).unwrap()
+                .compose(&GraphBuilder::cz(2, 0)).unwrap()
+                .compose(&GraphBuilder::gadget("xyz", Phase::new(1.25)).unwrap()).unwrap(),
+            GraphBuilder::cx(0, 1)
+                .compose(&GraphBuilder::cx(1, 2)).unwrap()
+                .compose(&GraphBuilder::pauli_z(1)).unwrap()
+                .compose(&GraphBuilder::cx(2, 0)).unwrap()
+                .compose(&GraphBuilder::cx(0, 1)).unwrap()
+                .compose(&GraphBuilder::gadget("zzi", Phase::new(0.25)).unwrap()).unwrap()
+                .compose(&GraphBuilder::cx(1, 2)).unwrap()
+                .compose(&GraphBuilder::gadget("zzi", Phase::new(1.5)).unwrap()).unwrap(),
+            GraphBuilder::gadget("zz", Phase::new(0.125)).unwrap()
+                .compose(&GraphBuilder::gadget("zz", Phase::new(1.125)).unwrap()).unwrap(),
+            GraphBuilder::cx(0, 1)
+                .compose(&GraphBuilder::z_plus(0)).unwrap()
+                .compose(&GraphBuilder::cz(0, 1)).unwrap()
+                .compose(&GraphBuilder::x_plus(1)).unwrap()
+                .compose(&GraphBuilder::cx(1, 0)).unwrap(),
+        ];
+        for (strategy, mut graph) in [Strategy::InteriorClifford, Strategy::Clifford, Strategy::FullReduce]
+            .into_iter()
+            .flat_map(|strategy| circuits.iter().map(move |circuit| (strategy, circuit.clone())))
+        {
+            let expected = graph.to_matrix();
+            let derivation = graph.trace(strategy);
+            assert!(!derivation.steps.is_empty());
+            for (step, graph) in derivation.graphs().unwrap().iter().enumerate() {
+                let rule = step.checked_sub(1).map(|step| derivation.steps[step].rule);
+                assert!(graph.to_matrix().approx_eq(&expected, TOLERANCE), "{strategy:?} step {step}: {rule:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use num_complex::Complex64;
+use std::ops::Mul;
+
+/// A dense complex matrix stored in row-major order
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<Complex64>,
+}
+
+impl Matrix {
+    /// Creates a `rows` × `cols` matrix from its entries in row-major order.
+    ///
+    /// Panics if `data` does not have `rows * cols` entries.
+    pub fn new(rows: usize, cols: usize, data: Vec<Complex64>) -> Self {
+        assert_eq!(data.len(), rows * cols, "matrix data does not match its shape");
+        Matrix { rows, cols, data }
+    }
+
+    pub fn zeros(rows: usize, cols: usize) -> Self {
+        Matrix::new(rows, cols, vec![Complex64::ZERO; rows * cols])
+    }
+
+    pub fn identity(size: usize) -> Self {
+        let mut matrix = Matrix::zeros(size, size);
+        for i in 0..size {
+            matrix.data[i * size + i] = Complex64::ONE;
+        }
+        matrix
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns the entry in row `row` and column `col`.
+    ///
+    /// Panics if either is out of range.
+    pub fn get(&self, row: usize, col: usize) -> Complex64 {
+        assert!(row < self.rows && col < self.cols, "entry ({row}, {col}) out of range");
+        self.data[row * self.cols + col]
+    }
+
+    /// Returns the entries in row-major order.
+    pub fn data(&self) -> &[Complex64] {
+        &self.data
+    }
+
+    /// Returns the matrix with every entry multiplied by `factor`.
+    pub fn scale(&self, factor: Complex64) -> Matrix {
+        Matrix::new(self.rows, self.cols, self.data.iter().map(|&entry| entry * factor).collect())
+    }
+
+    /// Returns the conjugate transpose of the matrix.
+    pub fn adjoint(&self) -> Matrix {
+        let mut data = Vec::with_capacity(self.data.len());
+        for col in 0..self.cols {
+            for row in 0..self.rows {
+                data.push(self.get(row, col).conj());
+            }
+        }
+        Matrix::new(self.cols, self.rows, data)
+    }
+
+    /// Returns the Kronecker product of `self` and `other`, with `self` acting on the most
+    /// significant bits.
+    ///
+    /// Runs in **O(nm)** time, where *n* and *m* are the numbers of entries of each matrix.
+    pub fn kron(&self, other: &Matrix) -> Matrix {
+        let (rows, cols) = (self.rows * other.rows, self.cols * other.cols);
+        let mut data = Vec::with_capacity(rows * cols);
+        for row in 0..rows {
+            for col in 0..cols {
+                let outer = self.get(row / other.rows, col / other.cols);
+                data.push(outer * other.get(row % other.rows, col % other.cols));
+            }
+        }
+        Matrix::new(rows, cols, data)
+    }
+
+    /// Returns true if both matrices have the same shape and every pair of entries differs by
+    /// at most `tolerance`.
+    pub fn approx_eq(&self, other: &Matrix, tolerance: f64) -> bool {
+        self.rows == other.rows
+            && self.cols == other.cols
+            && self.data.iter().zip(&other.data).all(|(a, b)| (a - b).norm() <= tolerance)
+    }
+
+    /// Returns true if `self` is a non-zero multiple of `other`.
+    ///
+    /// Both matrices are divided by their entry at the position of the largest entry of `self`
+    /// before being compared with `tolerance`. Matrices whose entries are all within `tolerance`
+    /// of zero are only proportional to each other.
+    pub fn is_proportional_to(&self, other: &Matrix, tolerance: f64) -> bool {
+        if self.rows != other.rows || self.cols != other.cols {
+            return false;
+        }
+        let is_zero = |matrix: &Matrix| matrix.data.iter().all(|entry| entry.norm() <= tolerance);
+        let Some(largest) = self.largest_entry() else { return true };
+        if is_zero(self) || is_zero(other) {
+            return is_zero(self) && is_zero(other);
+        }
+
+        let (a, b) = (self.data[largest], other.data[largest]);
+        if b.norm() <= tolerance {
+            return false;
+        }
+        self.scale(a.inv()).approx_eq(&other.scale(b.inv()), tolerance)
+    }
+
+    /// Returns the position of the entry with the largest norm, if the matrix is not empty.
+    fn largest_entry(&self) -> Option<usize> {
+        self.data.iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.norm().total_cmp(&b.norm()))
+            .map(|(index, _)| index)
+    }
+}
+
+impl Mul for &Matrix {
+    type Output = Matrix;
+
+    /// Returns the matrix product `self · other`.
+    ///
+    /// Panics if the number of columns of `self` differs from the number of rows of `other`.
+    fn mul(self, other: &Matrix) -> Matrix {
+        assert_eq!(self.cols, other.rows, "matrix shapes do not match");
+        let mut product = Matrix::zeros(self.rows, other.cols);
+        for row in 0..self.rows {
+            for k in 0..self.cols {
+                let entry = self.get(row, k);
+                if entry == Complex64::ZERO {
+                    continue;
+                }
+                for col in 0..other.cols {
+                    product.data[row * other.cols + col] += entry * other.get(k, col);
+                }
+            }
+        }
+        product
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Matrix;
+    use num_complex::Complex64;
+
+    fn real(entries: &[f64]) -> Vec<Complex64> {
+        entries.iter().map(|&entry| Complex64::new(entry, 0.0)).collect()
+    }
+
+    #[test]
+    fn kron_puts_self_on_most_significant_bits() {
+        let x = Matrix::new(2, 2, real(&[0.0, 1.0, 1.0, 0.0]));
+        let product = x.kron(&Matrix::identity(2));
+        assert_eq!(product.get(2, 0), Complex64::ONE);
+        assert_eq!(product.get(1, 2), Complex64::ZERO);
+        assert_eq!(&product * &product, Matrix::identity(4));
+    }
+
+    #[test]
+    fn proportionality() {
+        let a = Matrix::new(2, 2, real(&[1.0, 2.0, 3.0, 4.0]));
+        let b = a.scale(Complex64::new(0.0, -2.5));
+        assert!(a.is_proportional_to(&b, 1e-12));
+        assert!(b.is_proportional_to(&a, 1e-12));
+        assert!(!a.is_proportional_to(&Matrix::identity(2), 1e-12));
+        assert!(!a.is_proportional_to(&Matrix::zeros(2, 2), 1e-12));
+        assert!(Matrix::zeros(2, 2).is_proportional_to(&Matrix::zeros(2, 2), 1e-12));
+        assert!(!a.is_proportional_to(&Matrix::zeros(2, 1), 1e-12));
+    }
+
+    #[test]
+    fn adjoint_conjugates_and_transposes() {
+        let a = Matrix::new(1, 2, vec![Complex64::new(1.0, 1.0), Complex64::new(0.0, 2.0)]);
+        let adjoint = a.adjoint();
+        assert_eq!((adjoint.rows(), adjoint.cols()), (2, 1));
+        assert_eq!(adjoint.get(1, 0), Complex64::new(0.0, -2.0));
+    }
+}
@@ -0,0 +1,30 @@
+mod matrix;
+mod dense;
+mod contract;
+mod evaluate;
+mod equality;
+mod random;
+
+pub use matrix::Matrix;
+pub use num_complex::Complex64;
+
+/// Evaluation of diagrams with constant phases
+///
+/// Diagrams with symbolic phases must have every variable substituted first; evaluating one
+/// that does not panics.
+pub trait Evaluate {
+    /// Returns the linear map of the diagram as a 2^outputs × 2^inputs matrix
+    fn to_matrix(&self) -> Matrix;
+
+    /// Returns the amplitude ⟨`outputs`|D|`inputs`⟩ of the diagram D between two basis states
+    fn amplitude(&self, outputs: &[bool], inputs: &[bool]) -> Complex64;
+}
+
+pub trait SemanticEquality {
+    /// Returns true if both diagrams evaluate to the same linear map up to a non-zero scalar
+    fn equals_semantically(&self, other: &Self, tolerance: f64) -> bool;
+
+    /// Returns true if both diagrams agree up to a non-zero scalar on `samples` random product
+    /// states drawn from `seed`
+    fn equals_semantically_randomized(&self, other: &Self, samples: usize, seed: u64, tolerance: f64) -> bool;
+}
@@ -0,0 +1,51 @@
+use num_complex::Complex64;
+
+/// A small SplitMix64 generator, used to draw reproducible random states
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniform sample from [-1, 1).
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+
+    /// Returns a random single-qubit vector, whose entries are uniform over the square
+    /// [-1, 1) × [-1, 1) of the complex plane.
+    pub(crate) fn qubit(&mut self) -> [Complex64; 2] {
+        [
+            Complex64::new(self.next_f64(), self.next_f64()),
+            Complex64::new(self.next_f64(), self.next_f64()),
+        ]
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+
+    #[test]
+    fn is_reproducible_and_in_range() {
+        let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+        for _ in 0..1000 {
+            let sample = a.next_f64();
+            assert_eq!(sample, b.next_f64());
+            assert!((-1.0..1.0).contains(&sample));
+        }
+        assert_ne!(Rng::new(7).next_u64(), Rng::new(8).next_u64());
+    }
+}